@@ -0,0 +1,108 @@
+//! Recent changes across every counter, kept in localStorage for the
+//! history page.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use yew::prelude::*;
+use yew::services::{IntervalService, Task};
+
+use counter::Total;
+use effects::{BrowserClock, Clock};
+use format::{self, TimeFormat};
+use storage::{self, Store, Upgrade};
+
+/// How many changes the log keeps.
+const LOG_LIMIT: usize = 100;
+
+/// How often the relative times on the history page are brought up to
+/// date. The finest unit they show is seconds.
+const REFRESH: Duration = Duration::from_secs(1);
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Change {
+    /// Milliseconds since the Unix epoch.
+    pub at: f64,
+    pub counter: String,
+    pub value: Total,
+}
+
+/// Oldest change first.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Log {
+    changes: VecDeque<Change>,
+}
+
+impl Upgrade for Log {}
+
+impl Log {
+    pub fn load(store: &mut Store) -> Self {
+        store.load().unwrap_or_default()
+    }
+
+    /// Appends a change and drops the oldest past the limit.
+    pub fn push(&mut self, change: Change) {
+        self.changes.push_back(change);
+        while self.changes.len() > LOG_LIMIT {
+            self.changes.pop_front();
+        }
+    }
+}
+
+/// The history page: the log, newest first.
+pub struct Activity {
+    log: Log,
+    now: f64,
+    tick: Box<Task>,
+}
+
+pub enum Msg {
+    Tick,
+}
+
+impl Component for Activity {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, mut link: ComponentLink<Self>) -> Self {
+        let log = Log::load(&mut Store::new(storage::ACTIVITY_KEY));
+        let tick = IntervalService::new().spawn(REFRESH, link.send_back(|_| Msg::Tick));
+        Activity {
+            log,
+            now: BrowserClock.now(),
+            tick: Box::new(tick),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Tick => self.now = BrowserClock.now(),
+        }
+        !self.log.changes.is_empty()
+    }
+
+    fn destroy(&mut self) {
+        self.tick.cancel();
+    }
+}
+
+impl Renderable<Activity> for Activity {
+    fn view(&self) -> Html<Self> {
+        if self.log.changes.is_empty() {
+            return html! { <p>{ "Nothing has changed yet." }</p> };
+        }
+        let now = self.now;
+        html! {
+            <table class="activity",>
+                <tr><th>{ "When" }</th><th>{ "Counter" }</th><th>{ "Value" }</th></tr>
+                { for self.log.changes.iter().rev().map(|change| html! {
+                    <tr>
+                        <td>{ format::format(&TimeFormat::Relative, change.at, now) }</td>
+                        <td>{ &change.counter }</td>
+                        <td>{ &change.value }</td>
+                    </tr>
+                }) }
+            </table>
+        }
+    }
+}
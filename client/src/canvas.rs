@@ -0,0 +1,240 @@
+//! A `<canvas>` element as a component. It owns the element and its 2D
+//! context, and re-draws its `Drawing` whenever the props change or the
+//! mouse moves over it.
+
+use stdweb::traits::*;
+use stdweb::unstable::TryInto;
+use stdweb::web::event::{MouseMoveEvent, MouseOutEvent};
+use stdweb::web::html_element::CanvasElement;
+use stdweb::web::{document, CanvasRenderingContext2d, EventListenerHandle, FillRule, TextAlign, TextBaseline};
+use yew::prelude::*;
+use yew::virtual_dom::VNode;
+
+use paint::{Align, Drawing, Painter};
+
+pub struct Canvas<D>
+where
+    D: Drawing + Clone + PartialEq + Default + 'static,
+{
+    element: CanvasElement,
+    context: CanvasRenderingContext2d,
+    props: Props<D>,
+    pointer: Option<(f64, f64)>,
+    listeners: Vec<EventListenerHandle>,
+}
+
+pub enum Msg {
+    /// The mouse moved to this spot, or left the canvas.
+    Pointer(Option<(f64, f64)>),
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct Props<D> {
+    /// Size in CSS pixels.
+    pub width: u32,
+    pub height: u32,
+    pub drawing: D,
+}
+
+impl<D> Component for Canvas<D>
+where
+    D: Drawing + Clone + PartialEq + Default + 'static,
+{
+    type Message = Msg;
+    type Properties = Props<D>;
+
+    fn create(props: Self::Properties, mut link: ComponentLink<Self>) -> Self {
+        let (element, context) = new_canvas();
+        let moved = link.send_back(|event: MouseMoveEvent| {
+            Msg::Pointer(Some((event.offset_x(), event.offset_y())))
+        });
+        let left = link.send_back(|_: MouseOutEvent| Msg::Pointer(None));
+        let listeners = vec![
+            element.add_event_listener(move |event: MouseMoveEvent| moved.emit(event)),
+            element.add_event_listener(move |event: MouseOutEvent| left.emit(event)),
+        ];
+        let mut canvas = Canvas {
+            element,
+            context,
+            props,
+            pointer: None,
+            listeners,
+        };
+        canvas.resize();
+        canvas.draw();
+        canvas
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Pointer(pointer) => {
+                self.pointer = pointer;
+                self.draw();
+            }
+        }
+        // Drawing happens straight on the element; the DOM is unchanged.
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props == self.props {
+            return false;
+        }
+        self.props = props;
+        self.resize();
+        self.draw();
+        false
+    }
+
+    fn destroy(&mut self) {
+        for listener in self.listeners.drain(..) {
+            listener.remove();
+        }
+    }
+}
+
+impl<D> Canvas<D>
+where
+    D: Drawing + Clone + PartialEq + Default + 'static,
+{
+    fn resize(&mut self) {
+        resize(&self.element, self.props.width, self.props.height);
+    }
+
+    fn draw(&mut self) {
+        let (width, height) = (f64::from(self.props.width), f64::from(self.props.height));
+        let mut painter = ContextPainter(&self.context);
+        self.props.drawing.draw(&mut painter, width, height, self.pointer);
+    }
+}
+
+impl<D> Renderable<Canvas<D>> for Canvas<D>
+where
+    D: Drawing + Clone + PartialEq + Default + 'static,
+{
+    fn view(&self) -> Html<Self> {
+        VNode::VRef(self.element.as_node().clone())
+    }
+}
+
+/// Creates a detached `<canvas>` element and its 2D context.
+pub fn new_canvas() -> (CanvasElement, CanvasRenderingContext2d) {
+    let element: CanvasElement = document()
+        .create_element("canvas")
+        .expect("canvas is a valid tag name")
+        .try_into()
+        .expect("a canvas tag makes a canvas element");
+    let context = element
+        .get_context()
+        .expect("canvases always have a 2D context");
+    (element, context)
+}
+
+/// Sets a canvas's size. Returns whether it changed, which also wipes it.
+pub fn resize(element: &CanvasElement, width: u32, height: u32) -> bool {
+    let mut changed = false;
+    if element.width() != width {
+        element.set_width(width);
+        changed = true;
+    }
+    if element.height() != height {
+        element.set_height(height);
+        changed = true;
+    }
+    changed
+}
+
+/// Draws `drawing` on an offscreen canvas and returns it as a PNG `data:`
+/// URL, or `None` if the browser won't encode it.
+pub fn to_png<D: Drawing>(drawing: &D, width: u32, height: u32) -> Option<String> {
+    let (element, context) = new_canvas();
+    resize(&element, width, height);
+    drawing.draw(&mut ContextPainter(&context), f64::from(width), f64::from(height), None);
+    element.to_data_url(Some("image/png"), None).ok()
+}
+
+/// Paints onto a canvas's 2D context.
+pub struct ContextPainter<'a>(pub &'a CanvasRenderingContext2d);
+
+impl<'a> Painter for ContextPainter<'a> {
+    fn clear_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.0.clear_rect(x, y, width, height);
+    }
+
+    fn polyline(&mut self, points: &[(f64, f64)], color: &str, width: f64) {
+        let context = self.0;
+        let mut points = points.iter();
+        if let Some(&(x, y)) = points.next() {
+            context.begin_path();
+            context.move_to(x, y);
+            for &(x, y) in points {
+                context.line_to(x, y);
+            }
+            context.set_stroke_style_color(color);
+            context.set_line_width(width);
+            context.stroke();
+        }
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: &str) {
+        self.0.set_fill_style_color(color);
+        self.0.fill_rect(x, y, width, height);
+    }
+
+    fn fill_polygon(&mut self, points: &[(f64, f64)], color: &str) {
+        let context = self.0;
+        let mut points = points.iter();
+        if let Some(&(x, y)) = points.next() {
+            context.begin_path();
+            context.move_to(x, y);
+            for &(x, y) in points {
+                context.line_to(x, y);
+            }
+            context.close_path();
+            context.set_fill_style_color(color);
+            context.fill(FillRule::NonZero);
+        }
+    }
+
+    fn fill_circle(&mut self, x: f64, y: f64, radius: f64, color: &str) {
+        let context = self.0;
+        context.begin_path();
+        context.arc(x, y, radius, 0.0, 2.0 * ::std::f64::consts::PI, false);
+        context.set_fill_style_color(color);
+        context.fill(FillRule::NonZero);
+    }
+
+    fn stroke_circle(&mut self, x: f64, y: f64, radius: f64, color: &str, width: f64) {
+        let context = self.0;
+        context.begin_path();
+        context.arc(x, y, radius, 0.0, 2.0 * ::std::f64::consts::PI, false);
+        context.set_stroke_style_color(color);
+        context.set_line_width(width);
+        context.stroke();
+    }
+
+    fn text(&mut self, text: &str, x: f64, y: f64, align: Align, color: &str) {
+        let context = self.0;
+        context.set_font("12px sans-serif");
+        context.set_text_align(match align {
+            Align::Start => TextAlign::Left,
+            Align::Center => TextAlign::Center,
+            Align::End => TextAlign::Right,
+        });
+        context.set_text_baseline(TextBaseline::Middle);
+        context.set_fill_style_color(color);
+        context.fill_text(text, x, y, None);
+    }
+
+    fn clip(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        let context = self.0;
+        context.save();
+        context.begin_path();
+        context.rect(x, y, width, height);
+        context.clip(FillRule::NonZero);
+    }
+
+    fn unclip(&mut self) {
+        self.0.restore();
+    }
+}
@@ -0,0 +1,185 @@
+//! A line chart of a counter's value over time, with axes that scale to fit
+//! the data and a tooltip for the point under the mouse.
+
+use std::cmp::Ordering;
+
+use paint::{Align, Drawing, Painter};
+use theme::Theme;
+
+/// Room around the plot for the axis labels.
+const LEFT: f64 = 48.0;
+const RIGHT: f64 = 12.0;
+const TOP: f64 = 12.0;
+const BOTTOM: f64 = 24.0;
+
+/// How many ticks each axis aims for.
+const TICKS: usize = 5;
+
+/// How close, in pixels, the mouse has to be to a point for its tooltip.
+const HOVER_RADIUS: f64 = 24.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    /// Milliseconds since the Unix epoch.
+    pub at: f64,
+    pub value: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct LineChart {
+    pub points: Vec<Point>,
+    /// Where the colours come from.
+    pub theme: Theme,
+}
+
+/// Maps data coordinates onto the plot area.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scale {
+    pub x: (f64, f64),
+    pub y: (f64, f64),
+    /// The plot area: left, top, right, bottom.
+    pub area: (f64, f64, f64, f64),
+}
+
+impl Scale {
+    pub fn x(&self, at: f64) -> f64 {
+        let (left, _, right, _) = self.area;
+        left + (at - self.x.0) / (self.x.1 - self.x.0) * (right - left)
+    }
+
+    pub fn y(&self, value: f64) -> f64 {
+        let (_, top, _, bottom) = self.area;
+        bottom - (value - self.y.0) / (self.y.1 - self.y.0) * (bottom - top)
+    }
+}
+
+impl LineChart {
+    /// Fits the axes to the points on a `width` by `height` surface. The
+    /// value axis is widened to whole ticks so the labels stay round.
+    pub fn scale(&self, width: f64, height: f64) -> Scale {
+        let area = (LEFT, TOP, (width - RIGHT).max(LEFT + 1.0), (height - BOTTOM).max(TOP + 1.0));
+        let (mut first, mut last) = (0.0, 0.0);
+        let (mut low, mut high) = (0.0, 0.0);
+        if let Some(point) = self.points.first() {
+            first = point.at;
+            last = point.at;
+            low = point.value as f64;
+            high = low;
+        }
+        for point in &self.points {
+            first = point.at.min(first);
+            last = point.at.max(last);
+            low = (point.value as f64).min(low);
+            high = (point.value as f64).max(high);
+        }
+        // A single instant or a flat line still needs a range to spread over.
+        if last - first < 1000.0 {
+            first = last - 1000.0;
+        }
+        if high - low < 1.0 {
+            low -= 1.0;
+            high += 1.0;
+        }
+        let ticks = ticks(low, high, TICKS);
+        let y = (ticks[0], ticks[ticks.len() - 1]);
+        Scale {
+            x: (first, last),
+            y,
+            area,
+        }
+    }
+
+    /// The index of the point nearest to `x` on screen, if it's within
+    /// reach of the mouse.
+    pub fn nearest(&self, scale: &Scale, x: f64) -> Option<usize> {
+        self.points
+            .iter()
+            .map(|point| (scale.x(point.at) - x).abs())
+            .enumerate()
+            .filter(|&(_, distance)| distance <= HOVER_RADIUS)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+            .map(|(idx, _)| idx)
+    }
+
+    fn draw_axes<P: Painter>(&self, painter: &mut P, scale: &Scale) {
+        let (left, top, right, bottom) = scale.area;
+        let (axis, grid) = (self.theme.muted, self.theme.border);
+        for value in ticks(scale.y.0, scale.y.1, TICKS) {
+            let y = scale.y(value);
+            painter.polyline(&[(left, y), (right, y)], grid, 1.0);
+            painter.text(&label(value), left - 6.0, y, Align::End, axis);
+        }
+        let seconds = (scale.x.1 - scale.x.0) / 1000.0;
+        for offset in ticks(0.0, seconds, TICKS) {
+            if offset > seconds {
+                break;
+            }
+            let x = scale.x(scale.x.0 + offset * 1000.0);
+            painter.polyline(&[(x, bottom), (x, bottom + 4.0)], axis, 1.0);
+            painter.text(&format!("{}s", label(offset)), x, bottom + 14.0, Align::Center, axis);
+        }
+        painter.polyline(&[(left, top), (left, bottom), (right, bottom)], axis, 1.0);
+    }
+
+    fn draw_tooltip<P: Painter>(&self, painter: &mut P, scale: &Scale, idx: usize) {
+        let point = self.points[idx];
+        let (x, y) = (scale.x(point.at), scale.y(point.value as f64));
+        painter.fill_circle(x, y, 4.0, self.theme.accent);
+        let seconds = (point.at - scale.x.0) / 1000.0;
+        let text = format!("{} at {:.1}s", point.value, seconds);
+        let width = 8.0 + 7.0 * text.chars().count() as f64;
+        // Keep the box on the surface near the right and top edges.
+        let (_, top, right, _) = scale.area;
+        let left = if x + 8.0 + width > right { x - 8.0 - width } else { x + 8.0 };
+        let box_top = (y - 28.0).max(top);
+        // The page's colours swapped, so it stands out from the chart.
+        painter.fill_rect(left, box_top, width, 20.0, self.theme.text);
+        painter.text(&text, left + 4.0, box_top + 10.0, Align::Start, self.theme.background);
+    }
+}
+
+impl Drawing for LineChart {
+    fn draw<P: Painter>(&self, painter: &mut P, width: f64, height: f64, pointer: Option<(f64, f64)>) {
+        painter.clear(width, height);
+        if self.points.is_empty() {
+            painter.text("No data yet", width / 2.0, height / 2.0, Align::Center, self.theme.muted);
+            return;
+        }
+        let scale = self.scale(width, height);
+        self.draw_axes(painter, &scale);
+        let line: Vec<(f64, f64)> = self
+            .points
+            .iter()
+            .map(|point| (scale.x(point.at), scale.y(point.value as f64)))
+            .collect();
+        painter.polyline(&line, self.theme.accent, 2.0);
+        if let Some(idx) = pointer.and_then(|(x, _)| self.nearest(&scale, x)) {
+            self.draw_tooltip(painter, &scale, idx);
+        }
+    }
+}
+
+/// Round tick positions covering `low..=high`, about `count` of them, on
+/// steps of 1, 2 or 5 times a power of ten.
+pub fn ticks(low: f64, high: f64, count: usize) -> Vec<f64> {
+    let span = (high - low).max(1e-9);
+    let rough = span / count.max(1) as f64;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|factor| factor * magnitude)
+        .find(|&step| step >= rough)
+        .unwrap_or(10.0 * magnitude);
+    let start = (low / step).floor() as i64;
+    let end = (high / step).ceil() as i64;
+    (start..=end).map(|idx| idx as f64 * step).collect()
+}
+
+/// Writes a tick value without a trailing `.0` on whole numbers.
+fn label(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", (value * 100.0).round() / 100.0)
+    }
+}
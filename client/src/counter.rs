@@ -0,0 +1,234 @@
+//! The counter's state transitions, kept free of browser APIs so they can be
+//! tested natively with `cargo test`.
+
+pub use shared::{Command, Config, Overflow, OverflowError, PnCounter, State, Total};
+
+use history::History;
+use logging::{field, Logger, Sink};
+
+/// How many steps `Counter::undo` can walk back.
+const HISTORY_LIMIT: usize = 100;
+
+pub struct Counter<S> {
+    config: Config,
+    /// Which replica this counter's own changes are attributed to.
+    replica: String,
+    state: State,
+    /// Past values rather than past states: undoing is a new change that
+    /// moves the value back, so it merges with other replicas like any other.
+    history: History<Total>,
+    logger: Logger<S>,
+}
+
+/// Everything needed to rebuild a counter, undo history included.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub config: Config,
+    pub state: State,
+    history: History<Total>,
+}
+
+impl Snapshot {
+    /// A snapshot of a counter at `state` with nothing to undo.
+    pub fn new(config: Config, state: State) -> Self {
+        Snapshot {
+            config,
+            state,
+            history: History::new(HISTORY_LIMIT),
+        }
+    }
+}
+
+impl<S: Sink> Counter<S> {
+    /// Creates a counter at `state`, pulled inside the configured bounds.
+    /// Local changes are recorded under `replica`, which must be unique
+    /// among everyone sharing the counter.
+    pub fn new(config: Config, replica: String, state: State, logger: Logger<S>) -> Self {
+        let mut counter = Counter {
+            config,
+            replica,
+            state,
+            history: History::new(HISTORY_LIMIT),
+            logger,
+        };
+        counter.clamp();
+        counter
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// The value, saturating at the `i64` limits.
+    pub fn value(&self) -> i64 {
+        self.state.value()
+    }
+
+    /// The exact value; see `State::total`.
+    pub fn total(&self) -> Total {
+        self.state.total()
+    }
+
+    pub fn replica(&self) -> &str {
+        &self.replica
+    }
+
+    /// Attributes further local changes to `replica`, e.g. while replaying
+    /// changes another tab made.
+    pub fn set_replica(&mut self, replica: String) {
+        self.replica = replica;
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn logger(&self) -> &Logger<S> {
+        &self.logger
+    }
+
+    pub fn logger_mut(&mut self) -> &mut Logger<S> {
+        &mut self.logger
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Applies a command. Returns whether the value changed; a command
+    /// refused under `Overflow::Error` is logged and changes nothing.
+    pub fn update(&mut self, command: Command) -> bool {
+        match self.try_update(command) {
+            Ok(changed) => changed,
+            Err(err) => {
+                self.logger.warn("overflow", vec![field("error", &err)]);
+                false
+            }
+        }
+    }
+
+    /// Like `update`, but hands back the error when the overflow policy
+    /// refuses the command.
+    pub fn try_update(&mut self, command: Command) -> Result<bool, OverflowError> {
+        // Snapshot once per command so a whole Bulk undoes as a single step.
+        let previous = self.total();
+        self.apply(&command)?;
+        if self.total() != previous {
+            self.history.record(previous);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Moves straight to `value`, pulled inside the configured bounds, as a
+    /// single undoable step. Returns whether the value changed.
+    pub fn set<T: Into<Total>>(&mut self, value: T) -> bool {
+        let previous = self.total();
+        let value = self.config.clamp_total(&value.into());
+        if value == previous {
+            return false;
+        }
+        self.logger.info(
+            "set",
+            vec![field("old", &previous), field("new", &value)],
+        );
+        self.move_to(value);
+        self.history.record(previous);
+        true
+    }
+
+    /// Steps back to the value before the last change. Returns `false` if
+    /// there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let mut value = self.total();
+        self.history.undo(&mut value) && self.move_to(value)
+    }
+
+    /// Re-applies the last undone change. Returns `false` if there was
+    /// nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let mut value = self.total();
+        self.history.redo(&mut value) && self.move_to(value)
+    }
+
+    /// Swaps in a new configuration, pulling the value inside the new
+    /// bounds. Returns whether the value changed.
+    pub fn configure(&mut self, config: Config) -> bool {
+        self.config = config;
+        self.clamp()
+    }
+
+    /// Folds in changes made elsewhere, e.g. by another tab. The merged
+    /// value is taken as-is and the history is kept, so local changes can
+    /// still be stepped back. Returns whether the state changed.
+    pub fn merge(&mut self, other: &PnCounter) -> bool {
+        let previous = self.state.clone();
+        self.state.counter.merge(other);
+        self.state != previous
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            config: self.config.clone(),
+            state: self.state.clone(),
+            history: self.history.clone(),
+        }
+    }
+
+    /// Returns to a snapshot exactly as it was taken, history included.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.config = snapshot.config;
+        self.state = snapshot.state;
+        self.history = snapshot.history;
+    }
+
+    /// Replaces the state outright, forgetting the undo/redo history.
+    pub fn reset(&mut self, state: State) {
+        self.state = state;
+        self.clamp();
+        self.history = History::new(HISTORY_LIMIT);
+    }
+
+    /// Records a local change that takes the value to `value`. Always
+    /// returns `true` so it can be chained.
+    fn move_to(&mut self, value: Total) -> bool {
+        let delta = &value - &self.total();
+        self.state.counter.add(&self.replica, delta);
+        true
+    }
+
+    /// Pulls the value inside the configured bounds. Returns whether it
+    /// moved.
+    fn clamp(&mut self) -> bool {
+        let value = self.config.clamp_total(&self.total());
+        value != self.total() && self.move_to(value)
+    }
+
+    fn apply(&mut self, command: &Command) -> Result<(), OverflowError> {
+        let logger = &mut self.logger;
+        self.state
+            .apply_with(&self.replica, &self.config, command, &mut |command, old, new| match *command {
+                Command::Bulk(ref list) => logger.debug(
+                    "bulk applied",
+                    vec![
+                        field("count", list.len()),
+                        field("old", old),
+                        field("new", new),
+                    ],
+                ),
+                _ => logger.info(
+                    "applied",
+                    vec![
+                        field("kind", command.kind()),
+                        field("old", old),
+                        field("new", new),
+                    ],
+                ),
+            })
+    }
+}
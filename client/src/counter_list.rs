@@ -0,0 +1,323 @@
+//! A parent component that keeps several named counters side by side.
+
+use serde_json::{self, Value};
+use yew::prelude::*;
+
+use activity::{Change, Log};
+use counter::{Config, Overflow, State, Total};
+use effects::{BrowserClock, Clock};
+use keys::Keymap;
+use link::Link;
+use replica;
+use route::Route;
+use settings::Settings;
+use storage::{self, Store, Upgrade};
+use theme::Theme;
+use {Model, Shortcut};
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Entry {
+    id: u32,
+    name: String,
+    state: State,
+    /// Overrides the list's overflow policy for this counter.
+    #[serde(default)]
+    overflow: Overflow,
+    /// Name of the server-side counter this one follows. Fixed when the
+    /// counter is added, so renaming it doesn't switch to another one.
+    #[serde(default)]
+    channel: String,
+}
+
+/// The part of the list that survives a reload.
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    next_id: u32,
+    counters: Vec<Entry>,
+}
+
+pub struct CounterList {
+    store: Store,
+    props: Props,
+    /// Every change any counter reported, for the history page.
+    activity: Log,
+    activity_store: Store,
+    next_id: u32,
+    counters: Vec<Entry>,
+    /// The counter keyboard shortcuts go to.
+    selected: Option<u32>,
+}
+
+pub enum Msg {
+    Add,
+    Remove(u32),
+    Rename(u32, String),
+    MoveUp(u32),
+    MoveDown(u32),
+    /// Points keyboard shortcuts at a counter.
+    Select(u32),
+    /// A child counter with the given id changed its state.
+    Changed(u32, State),
+    /// Picks a counter's overflow policy by name.
+    SetOverflow(u32, String),
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct Props {
+    /// The configuration every counter in the list runs with, before the
+    /// user's settings are applied.
+    pub config: Config,
+    pub settings: Settings,
+    /// Sync server every counter follows, each on its own channel.
+    pub sync_url: Option<String>,
+    /// Shows only the counter with this id.
+    pub focus: Option<u32>,
+    /// The theme in effect, for the counters' drawings.
+    pub theme: Theme,
+}
+
+impl Component for CounterList {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        let mut store = Store::new(storage::LIST_KEY);
+        let saved = store.load::<Saved>();
+        let mut activity_store = Store::new(storage::ACTIVITY_KEY);
+        let mut list = CounterList {
+            store,
+            props,
+            activity: Log::load(&mut activity_store),
+            activity_store,
+            next_id: 0,
+            counters: Vec::new(),
+            selected: None,
+        };
+        match saved {
+            Some(saved) => {
+                list.next_id = saved.next_id;
+                list.counters = saved.counters;
+                // Saved before channels were fixed, when counters followed
+                // whatever they were called.
+                for entry in &mut list.counters {
+                    if entry.channel.is_empty() {
+                        entry.channel = entry.name.clone();
+                    }
+                }
+            }
+            None => list.add(),
+        }
+        list.selected = list.props.focus.or_else(|| list.counters.first().map(|entry| entry.id));
+        list
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Add => self.add(),
+            Msg::Remove(id) => {
+                if let Some(idx) = self.find(id) {
+                    let entry = self.counters.remove(idx);
+                    Store::new(&storage::journal_key(&entry.channel)).clear();
+                }
+                if self.selected == Some(id) {
+                    self.selected = self.counters.first().map(|entry| entry.id);
+                }
+            }
+            Msg::Rename(id, name) => match self.find(id) {
+                Some(idx) => self.counters[idx].name = name,
+                None => return false,
+            },
+            Msg::MoveUp(id) => match self.find(id) {
+                Some(idx) if idx > 0 => self.counters.swap(idx, idx - 1),
+                _ => return false,
+            },
+            Msg::MoveDown(id) => match self.find(id) {
+                Some(idx) if idx + 1 < self.counters.len() => self.counters.swap(idx, idx + 1),
+                _ => return false,
+            },
+            Msg::Changed(id, state) => match self.find(id) {
+                Some(idx) if self.counters[idx].state != state => {
+                    self.activity.push(Change {
+                        at: BrowserClock.now(),
+                        counter: self.counters[idx].name.clone(),
+                        value: state.total(),
+                    });
+                    self.activity_store.save(&self.activity);
+                    self.counters[idx].state = state;
+                }
+                _ => return false,
+            },
+            Msg::SetOverflow(id, name) => match (self.find(id), name.parse()) {
+                (Some(idx), Ok(overflow)) => self.counters[idx].overflow = overflow,
+                _ => return false,
+            },
+            Msg::Select(id) => {
+                self.selected = Some(id);
+                return true;
+            }
+        }
+        self.save();
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props.focus.is_some() {
+            self.selected = props.focus;
+        }
+        self.props = props;
+        true
+    }
+}
+
+impl CounterList {
+    fn add(&mut self) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.counters.push(Entry {
+            id,
+            name: format!("Counter {}", id + 1),
+            state: State::initial(self.props.config.clamp(self.props.config.initial)),
+            overflow: self.props.config.overflow,
+            channel: format!("counter-{}", id),
+        });
+        if self.selected.is_none() {
+            self.selected = Some(id);
+        }
+    }
+
+    fn find(&self, id: u32) -> Option<usize> {
+        self.counters.iter().position(|entry| entry.id == id)
+    }
+
+    fn total(&self) -> Total {
+        self.counters
+            .iter()
+            .fold(Total::zero(), |sum, entry| &sum + &entry.state.total())
+    }
+
+    fn save(&mut self) {
+        let saved = Saved {
+            next_id: self.next_id,
+            counters: self.counters.clone(),
+        };
+        self.store.save(&saved);
+    }
+}
+
+/// An entry as saved before schema version 2, holding a plain value.
+#[derive(Deserialize)]
+struct EntryV1 {
+    id: u32,
+    name: String,
+    value: i64,
+}
+
+#[derive(Deserialize)]
+struct SavedV1 {
+    next_id: u32,
+    counters: Vec<EntryV1>,
+}
+
+impl Upgrade for Saved {
+    fn upgrade(version: u32, state: Value) -> Option<Self> {
+        match version {
+            1 => {
+                let old: SavedV1 = serde_json::from_value(state).ok()?;
+                let replica = replica::id();
+                let counters = old
+                    .counters
+                    .into_iter()
+                    .map(|entry| Entry {
+                        id: entry.id,
+                        channel: entry.name.clone(),
+                        name: entry.name,
+                        state: State::from_value(&replica, entry.value),
+                        overflow: Overflow::default(),
+                    })
+                    .collect();
+                Some(Saved {
+                    next_id: old.next_id,
+                    counters,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Renderable<CounterList> for CounterList {
+    fn view(&self) -> Html<Self> {
+        if let Some(id) = self.props.focus {
+            return match self.find(id) {
+                Some(idx) => html! {
+                    <ul class="counters",>
+                        { self.view_entry(&self.counters[idx]) }
+                    </ul>
+                },
+                None => html! {
+                    <p>
+                        { "There's no such counter. " }
+                        <Link: route=Route::Counters, label="See them all",/>
+                    </p>
+                },
+            };
+        }
+        html! {
+            <div>
+                <nav class="menu",>
+                    <button onclick=|_| Msg::Add,>{ "Add Counter" }</button>
+                </nav>
+                <ul class="counters",>
+                    { for self.counters.iter().map(|entry| self.view_entry(entry)) }
+                </ul>
+                <p>{ format!("Total: {}", self.total()) }</p>
+            </div>
+        }
+    }
+}
+
+impl CounterList {
+    fn view_entry(&self, entry: &Entry) -> Html<CounterList> {
+        let id = entry.id;
+        let (class, shortcuts) = if self.selected == Some(id) {
+            ("counter selected", Shortcut::keymap())
+        } else {
+            ("counter", Keymap::new())
+        };
+        html! {
+            <li class=class, onclick=|_| Msg::Select(id),>
+                <input class="name", type="text", value=&entry.name, oninput=|e| Msg::Rename(id, e.value),/>
+                <button onclick=|_| Msg::MoveUp(id),>{ "Up" }</button>
+                <button onclick=|_| Msg::MoveDown(id),>{ "Down" }</button>
+                <button onclick=|_| Msg::Remove(id),>{ "Remove" }</button>
+                <Link: route=Route::Counter(id), label="Open",/>
+                <label>
+                    { "On overflow " }
+                    <select onchange=|value| {
+                        let name = match value {
+                            ChangeData::Select(select) => select.value().unwrap_or_default(),
+                            _ => String::new(),
+                        };
+                        Msg::SetOverflow(id, name)
+                    },>
+                        { for Overflow::ALL.iter().map(|&policy| html! {
+                            <option value=policy.name(), selected=policy == entry.overflow,>{ policy.name() }</option>
+                        }) }
+                    </select>
+                </label>
+                <Model:
+                    config=self.props.settings.configure(&Config { overflow: entry.overflow, ..self.props.config.clone() }),
+                    confirm_decrement=self.props.settings.confirm_decrement,
+                    log_level=self.props.settings.log_level,
+                    value=Some(entry.state.clone()),
+                    onchange=move |state| Msg::Changed(id, state),
+                    sync_url=self.props.sync_url.clone(),
+                    sync_channel=entry.channel.clone(),
+                    shortcuts=shortcuts,
+                    share_url=self.props.focus == Some(id),
+                    theme=self.props.theme.clone(),
+                    />
+            </li>
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! A time-travel panel over a `Timeline`. Any component can host one: keep a
+//! `Timeline` of its state, route `Action`s back into `Timeline::handle`,
+//! and render `timeline.shown()` in place of the live state when it's set.
+
+use yew::prelude::*;
+
+use timeline::{Action, Moment, Timeline};
+
+/// Renders the panel. `describe` writes a state out for the list, and
+/// `send` wraps the panel's actions in the host's message type.
+pub fn view<C, T, F>(timeline: &Timeline<T>, describe: F, send: fn(Action) -> C::Message) -> Html<C>
+where
+    C: Component + Renderable<C>,
+    T: Clone,
+    F: Fn(&T) -> String,
+{
+    let status = match timeline.cursor() {
+        Some(idx) => format!("Previewing #{}", idx + 1),
+        None => "Live".to_string(),
+    };
+    html! {
+        <aside class="debugger",>
+            <nav class="menu",>
+                <button disabled=timeline.moments().len() < 2, onclick=|_| send(Action::Back),>{ "Back" }</button>
+                <button disabled=timeline.is_live(), onclick=|_| send(Action::Forward),>{ "Forward" }</button>
+                <button disabled=timeline.is_live(), onclick=|_| send(Action::Resume),>{ "Resume" }</button>
+                <span class="status",>{ status }</span>
+            </nav>
+            <ol class="moments",>
+                { for timeline.moments().iter().enumerate().map(|(idx, moment)| {
+                    view_moment(idx, moment, timeline.cursor() == Some(idx), &describe, send)
+                }) }
+            </ol>
+        </aside>
+    }
+}
+
+fn view_moment<C, T, F>(idx: usize, moment: &Moment<T>, selected: bool, describe: &F, send: fn(Action) -> C::Message) -> Html<C>
+where
+    C: Component + Renderable<C>,
+    F: Fn(&T) -> String,
+{
+    let class = if selected { "moment selected" } else { "moment" };
+    let style = format!("padding-left: {}em", moment.depth * 2);
+    html! {
+        <li class=class, style=style, onclick=|_| send(Action::Preview(idx)),>
+            <code>{ &moment.label }</code>
+            { " → " }
+            { describe(&moment.state) }
+        </li>
+    }
+}
@@ -0,0 +1,30 @@
+//! Hands generated files to the user through a temporary in-page download
+//! link, so nothing needs a round trip to the server.
+
+/// Offers `content` as a file called `name`.
+pub fn save(name: &str, mime: &str, content: &str) {
+    let url = js! {
+        return URL.createObjectURL(new Blob([@{content}], { type: @{mime} }));
+    };
+    let url = url.into_string().unwrap_or_default();
+    save_url(name, &url);
+    // Some browsers cancel the download if the URL goes away before it has
+    // started, so wait a tick.
+    js! { @(no_return)
+        var url = @{url};
+        setTimeout(function() { URL.revokeObjectURL(url); }, 0);
+    }
+}
+
+/// Offers whatever `url` points at, e.g. a `data:` URL, as a file called
+/// `name`.
+pub fn save_url(name: &str, url: &str) {
+    js! { @(no_return)
+        var link = document.createElement("a");
+        link.href = @{url};
+        link.download = @{name};
+        document.body.appendChild(link);
+        link.click();
+        document.body.removeChild(link);
+    }
+}
@@ -0,0 +1,25 @@
+//! Side effects the app needs, behind traits so tests can swap in their own
+//! implementations.
+
+pub trait Clock {
+    /// Milliseconds since the Unix epoch.
+    fn now(&self) -> f64;
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use self::browser::BrowserClock;
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use stdweb::web::Date;
+
+    use super::Clock;
+
+    pub struct BrowserClock;
+
+    impl Clock for BrowserClock {
+        fn now(&self) -> f64 {
+            Date::now()
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Parsing what the user types into the value field.
+
+use std::fmt;
+
+use counter::{Config, Overflow, Total};
+
+/// Why typed text isn't a value the counter can take.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EntryError {
+    Empty,
+    NotANumber(String),
+    /// Outside the configured bounds, which are given when set.
+    OutOfRange {
+        value: Total,
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    /// Past the `i64` limits, for a counter that isn't
+    /// `Overflow::Unbounded`.
+    Overflow,
+}
+
+impl fmt::Display for EntryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EntryError::Empty => write!(f, "enter a number"),
+            EntryError::NotANumber(ref text) => write!(f, "`{}` isn't a whole number", text),
+            EntryError::OutOfRange { ref value, min, max } => match (min, max) {
+                (Some(min), Some(max)) => write!(f, "{} is outside {}..={}", value, min, max),
+                (Some(min), None) => write!(f, "{} is below the minimum of {}", value, min),
+                (None, Some(max)) => write!(f, "{} is above the maximum of {}", value, max),
+                (None, None) => write!(f, "{} is out of range", value),
+            },
+            EntryError::Overflow => write!(f, "that number is too large"),
+        }
+    }
+}
+
+/// Reads `text` as a new value for a counter currently at `current`.
+///
+/// A leading `+` or `-` adjusts the current value, so `+10` adds ten and
+/// `-3` takes three away. Anything else, or a number after `=` (as in
+/// `=-3`), is taken as the value itself. The result must fit the
+/// configured bounds.
+pub fn parse(text: &str, current: &Total, config: &Config) -> Result<Total, EntryError> {
+    let text = text.trim();
+    let mut rest = text.chars();
+    let (relative, digits) = if rest.next() == Some('=') {
+        (false, rest.as_str().trim())
+    } else {
+        (text.starts_with('+') || text.starts_with('-'), text)
+    };
+    if digits.is_empty() {
+        return Err(EntryError::Empty);
+    }
+    let unsigned = digits.trim_start_matches(&['+', '-'][..]);
+    if unsigned.is_empty() || digits.len() - unsigned.len() > 1 || !unsigned.chars().all(|c| c.is_ascii_digit()) {
+        return Err(EntryError::NotANumber(text.to_string()));
+    }
+    let number: Total = digits.parse().map_err(|_| EntryError::NotANumber(text.to_string()))?;
+    let value = if relative { current + &number } else { number };
+    if config.overflow != Overflow::Unbounded && value.to_i64().is_none() {
+        return Err(EntryError::Overflow);
+    }
+    if config.clamp_total(&value) != value {
+        return Err(EntryError::OutOfRange {
+            value,
+            min: config.min,
+            max: config.max,
+        });
+    }
+    Ok(value)
+}
@@ -0,0 +1,110 @@
+//! Formatting of timestamps (milliseconds since the Unix epoch) for display.
+
+const MS_PER_SECOND: i64 = 1000;
+const MS_PER_MINUTE: i64 = 60 * MS_PER_SECOND;
+const MS_PER_HOUR: i64 = 60 * MS_PER_MINUTE;
+const MS_PER_DAY: i64 = 24 * MS_PER_HOUR;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimeFormat {
+    /// `2018-10-21T14:03:09Z`
+    Iso8601,
+    /// `3 minutes ago`, measured against the current time.
+    Relative,
+    /// A short date and time in the user's locale. Off the browser this
+    /// falls back to `21 Oct 2018 14:03` in UTC.
+    LocaleShort,
+    /// ISO 8601 shifted to a fixed offset from UTC, in minutes.
+    UtcOffset(i32),
+}
+
+/// Formats the instant `at`; `now` is only consulted by `Relative`.
+pub fn format(format: &TimeFormat, at: f64, now: f64) -> String {
+    match *format {
+        TimeFormat::Iso8601 => iso8601(at, 0),
+        TimeFormat::Relative => relative(at, now),
+        TimeFormat::LocaleShort => short(at),
+        TimeFormat::UtcOffset(minutes) => iso8601(at, minutes),
+    }
+}
+
+fn iso8601(at: f64, offset_minutes: i32) -> String {
+    let (date, time) = civil(at as i64 + i64::from(offset_minutes) * MS_PER_MINUTE);
+    let zone = if offset_minutes == 0 {
+        "Z".to_string()
+    } else {
+        let sign = if offset_minutes < 0 { '-' } else { '+' };
+        let minutes = offset_minutes.abs();
+        format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+    };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}",
+        date.0, date.1, date.2, time.0, time.1, time.2, zone
+    )
+}
+
+fn short(at: f64) -> String {
+    let ((year, month, day), (hour, minute, _)) = civil(at as i64);
+    format!(
+        "{} {} {} {:02}:{:02}",
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        hour,
+        minute
+    )
+}
+
+fn relative(at: f64, now: f64) -> String {
+    let delta = now as i64 - at as i64;
+    let magnitude = delta.abs();
+    if magnitude < 10 * MS_PER_SECOND {
+        return "just now".to_string();
+    }
+    let (amount, unit) = if magnitude < MS_PER_MINUTE {
+        (magnitude / MS_PER_SECOND, "second")
+    } else if magnitude < MS_PER_HOUR {
+        (magnitude / MS_PER_MINUTE, "minute")
+    } else if magnitude < MS_PER_DAY {
+        (magnitude / MS_PER_HOUR, "hour")
+    } else {
+        (magnitude / MS_PER_DAY, "day")
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    if delta > 0 {
+        format!("{} {}{} ago", amount, unit, plural)
+    } else {
+        format!("in {} {}{}", amount, unit, plural)
+    }
+}
+
+/// Splits epoch milliseconds into a proleptic Gregorian `(year, month, day)`
+/// and `(hour, minute, second)`.
+fn civil(ms: i64) -> ((i64, u32, u32), (u32, u32, u32)) {
+    let (mut days, mut ms_of_day) = (ms / MS_PER_DAY, ms % MS_PER_DAY);
+    if ms_of_day < 0 {
+        days -= 1;
+        ms_of_day += MS_PER_DAY;
+    }
+    let time = (
+        (ms_of_day / MS_PER_HOUR) as u32,
+        (ms_of_day % MS_PER_HOUR / MS_PER_MINUTE) as u32,
+        (ms_of_day % MS_PER_MINUTE / MS_PER_SECOND) as u32,
+    );
+
+    // Howard Hinnant's days-to-civil algorithm, with eras starting on 1 March.
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    ((year, month, day), time)
+}
@@ -0,0 +1,70 @@
+//! Bounded undo/redo history of state snapshots.
+
+use std::collections::VecDeque;
+use std::mem;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct History<T> {
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    limit: usize,
+}
+
+impl<T> History<T> {
+    /// Creates an empty history that keeps at most `limit` undo steps.
+    pub fn new(limit: usize) -> Self {
+        History {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Records `previous` as the state to go back to. Anything that was
+    /// undone before this point can no longer be redone.
+    pub fn record(&mut self, previous: T) {
+        self.redo.clear();
+        self.push_undo(previous);
+    }
+
+    /// Swaps `current` with the last recorded state. Returns `false` if
+    /// there was nothing to undo.
+    pub fn undo(&mut self, current: &mut T) -> bool {
+        match self.undo.pop_back() {
+            Some(previous) => {
+                let undone = mem::replace(current, previous);
+                self.redo.push(undone);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Swaps `current` with the last undone state. Returns `false` if
+    /// there was nothing to redo.
+    pub fn redo(&mut self, current: &mut T) -> bool {
+        match self.redo.pop() {
+            Some(next) => {
+                let redone = mem::replace(current, next);
+                self.push_undo(redone);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    fn push_undo(&mut self, state: T) {
+        self.undo.push_back(state);
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! An append-only record of everything that happened to a counter, with
+//! enough detail to rebuild it by replaying. Exports as JSON Lines, one
+//! entry per line.
+
+use std::fmt;
+
+use serde_json;
+
+use counter::{Command, Config, Counter, PnCounter, Snapshot, State, Total};
+use effects::Clock;
+use logging::{Level, Logger, NoopSink, Sink};
+
+/// Something that happened to a counter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Event {
+    /// The counter was (re)started from this snapshot. Replays can begin at
+    /// the latest one instead of at the first entry.
+    Snapshot(Snapshot),
+    Command(Command),
+    /// The value was set directly.
+    Set(Total),
+    Undo,
+    Redo,
+    Configure(Config),
+    /// Changes made by other replicas arrived.
+    Merge(PnCounter),
+    /// The state was replaced outright and the history forgotten.
+    Reset(State),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// Position in the journal. Consecutive, but not necessarily from zero
+    /// once old entries are dropped.
+    pub seq: u64,
+    /// Milliseconds since the Unix epoch.
+    pub at: f64,
+    /// The replica that made the change, or `None` if it arrived from
+    /// elsewhere.
+    pub replica: Option<String>,
+    /// Sequence number of the `Bulk` command this one ran inside. Replays
+    /// skip these, since running the parent runs them too.
+    pub parent: Option<u64>,
+    pub event: Event,
+}
+
+/// Why a JSON Lines journal couldn't be imported.
+#[derive(Debug, PartialEq)]
+pub struct ImportError {
+    /// 1-based line number.
+    pub line: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+pub struct Journal<C> {
+    clock: C,
+    entries: Vec<Entry>,
+}
+
+impl<C: Clock> Journal<C> {
+    pub fn new(clock: C) -> Self {
+        Journal {
+            clock,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// How many entries were recorded after the latest snapshot, or in all
+    /// if there is none.
+    pub fn since_snapshot(&self) -> usize {
+        self.entries
+            .iter()
+            .rev()
+            .take_while(|entry| !matches!(entry.event, Event::Snapshot(_)))
+            .count()
+    }
+
+    /// Drops the oldest entries until at most `limit` are left. Only cuts
+    /// just before a snapshot, so what's left still replays; keeps more
+    /// than `limit` if there's no snapshot to cut at.
+    pub fn trim(&mut self, limit: usize) {
+        if self.entries.len() <= limit {
+            return;
+        }
+        let earliest = self.entries.len() - limit;
+        let start = self.entries[earliest..]
+            .iter()
+            .position(|entry| matches!(entry.event, Event::Snapshot(_)));
+        if let Some(start) = start {
+            self.entries.drain(..earliest + start);
+        }
+    }
+
+    /// Appends `event`, stamped with the current time. A `Bulk` command is
+    /// followed by an entry for each of its children, nested ones included.
+    /// Returns the event's sequence number.
+    pub fn record(&mut self, replica: Option<&str>, event: Event) -> u64 {
+        let at = self.clock.now();
+        self.push(at, replica, None, event)
+    }
+
+    /// Rebuilds `counter` by replaying every entry from the first.
+    pub fn replay<S: Sink>(&self, counter: &mut Counter<S>) {
+        replay(&self.entries, counter);
+    }
+
+    /// Rebuilds `counter` from the latest snapshot, skipping everything
+    /// before it. Falls back to a full replay if there is no snapshot.
+    pub fn replay_from_snapshot<S: Sink>(&self, counter: &mut Counter<S>) {
+        let mut start = 0;
+        for (idx, entry) in self.entries.iter().enumerate().rev() {
+            if let Event::Snapshot(_) = entry.event {
+                start = idx;
+                break;
+            }
+        }
+        replay(&self.entries[start..], counter);
+    }
+
+    /// The value a replay from the latest snapshot ends at, worked out on a
+    /// scratch counter that starts from `config` so nothing live is touched.
+    pub fn replayed_total(&self, config: &Config) -> Total {
+        let logger = Logger::new("replay", Level::Error, NoopSink);
+        let mut scratch = Counter::new(config.clone(), "replay".into(), State::default(), logger);
+        self.replay_from_snapshot(&mut scratch);
+        scratch.total()
+    }
+
+    /// Writes the journal out as JSON Lines.
+    pub fn to_json_lines(&self) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            out.push_str(&serde_json::to_string(entry).expect("journal entries always serialize"));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Replaces the journal with one read from JSON Lines, as written by
+    /// `to_json_lines`. Blank lines are ignored. On error the journal is
+    /// left as it was.
+    pub fn import(&mut self, text: &str) -> Result<(), ImportError> {
+        let mut entries: Vec<Entry> = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let error = |reason: String| ImportError {
+                line: idx + 1,
+                reason,
+            };
+            let entry: Entry = serde_json::from_str(line).map_err(|err| error(err.to_string()))?;
+            check(&entries, &entry).map_err(error)?;
+            entries.push(entry);
+        }
+        self.entries = entries;
+        Ok(())
+    }
+
+    /// Replaces the journal with entries saved earlier, e.g. from
+    /// `entries`. Checked like `import`, with lines counted from one.
+    pub fn restore(&mut self, entries: Vec<Entry>) -> Result<(), ImportError> {
+        for (idx, entry) in entries.iter().enumerate() {
+            check(&entries[..idx], entry).map_err(|reason| ImportError { line: idx + 1, reason })?;
+        }
+        self.entries = entries;
+        Ok(())
+    }
+
+    /// Moves on to another channel's journal: the entries saved for it, or
+    /// an empty journal if there are none or they don't check out. Save
+    /// this one first; its entries are gone afterwards.
+    pub fn switch(&mut self, saved: Option<Vec<Entry>>) -> Result<(), ImportError> {
+        self.entries.clear();
+        match saved {
+            Some(entries) => self.restore(entries),
+            None => Ok(()),
+        }
+    }
+
+    fn push(&mut self, at: f64, replica: Option<&str>, parent: Option<u64>, event: Event) -> u64 {
+        let seq = self.entries.last().map_or(0, |entry| entry.seq + 1);
+        let children = match event {
+            Event::Command(Command::Bulk(ref list)) => list.clone(),
+            _ => Vec::new(),
+        };
+        self.entries.push(Entry {
+            seq,
+            at,
+            replica: replica.map(String::from),
+            parent,
+            event,
+        });
+        for child in children {
+            self.push(at, replica, Some(seq), Event::Command(child));
+        }
+        seq
+    }
+}
+
+/// Whether `entry` can follow `earlier`: numbered next, and nested only
+/// inside one of them.
+fn check(earlier: &[Entry], entry: &Entry) -> Result<(), String> {
+    if let Some(last) = earlier.last() {
+        if entry.seq != last.seq + 1 {
+            return Err(format!("expected entry {}, found {}", last.seq + 1, entry.seq));
+        }
+    }
+    if let Some(parent) = entry.parent {
+        if parent >= entry.seq || earlier.iter().all(|other| other.seq != parent) {
+            return Err(format!("parent {} is not an earlier entry", parent));
+        }
+    }
+    Ok(())
+}
+
+/// Applies `entries` to `counter` in order, each as the replica that made
+/// it. The counter's own replica is put back afterwards.
+fn replay<S: Sink>(entries: &[Entry], counter: &mut Counter<S>) {
+    let own = counter.replica().to_string();
+    for entry in entries.iter().filter(|entry| entry.parent.is_none()) {
+        if let Some(ref replica) = entry.replica {
+            counter.set_replica(replica.clone());
+        }
+        match entry.event {
+            Event::Snapshot(ref snapshot) => counter.restore(snapshot.clone()),
+            Event::Command(ref command) => {
+                counter.update(command.clone());
+            }
+            Event::Set(ref value) => {
+                counter.set(value.clone());
+            }
+            Event::Undo => {
+                counter.undo();
+            }
+            Event::Redo => {
+                counter.redo();
+            }
+            Event::Configure(ref config) => {
+                counter.configure(config.clone());
+            }
+            Event::Merge(ref other) => {
+                counter.merge(other);
+            }
+            Event::Reset(ref state) => counter.reset(state.clone()),
+        }
+    }
+    counter.set_replica(own);
+}
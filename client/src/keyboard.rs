@@ -0,0 +1,53 @@
+//! Listens for key presses anywhere on the page and reports them as
+//! `Chord`s, skipping the ones typed into form fields.
+
+use stdweb::traits::*;
+use stdweb::unstable::TryInto;
+use stdweb::web::event::KeyDownEvent;
+use stdweb::web::{window, EventListenerHandle, HtmlElement};
+use yew::callback::Callback;
+
+use keys::{self, Chord};
+
+/// Stops listening when dropped.
+pub struct KeyboardListener {
+    handle: Option<EventListenerHandle>,
+}
+
+impl KeyboardListener {
+    pub fn new(callback: Callback<Chord>) -> Self {
+        let handle = window().add_event_listener(move |event: KeyDownEvent| {
+            if typing(&event) {
+                return;
+            }
+            let chord = Chord::new(
+                &event.key(),
+                event.ctrl_key(),
+                event.alt_key(),
+                event.shift_key(),
+                event.meta_key(),
+            );
+            callback.emit(chord);
+        });
+        KeyboardListener { handle: Some(handle) }
+    }
+}
+
+impl Drop for KeyboardListener {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.remove();
+        }
+    }
+}
+
+fn typing(event: &KeyDownEvent) -> bool {
+    let element: Option<HtmlElement> = event.target().and_then(|target| target.try_into().ok());
+    match element {
+        Some(element) => {
+            let editable = js! { return @{&element}.isContentEditable; };
+            keys::is_typing(&element.node_name(), editable.try_into().unwrap_or(false))
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,137 @@
+//! Key chords and the bindings from chords to actions, kept free of browser
+//! types so they can be tested natively.
+
+use std::fmt;
+
+/// A key plus the modifiers held with it, e.g. `Ctrl+Z`.
+///
+/// Keys are named as the browser's `KeyboardEvent.key` names them. Shift
+/// only counts for letters: for other characters it's already part of the
+/// key, so `?` matches whether or not the keyboard needs Shift for it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub key: String,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+}
+
+impl Chord {
+    /// Builds a chord from the parts of a key event.
+    pub fn new(key: &str, ctrl: bool, alt: bool, shift: bool, meta: bool) -> Self {
+        let mut chars = key.chars();
+        let (key, shift) = match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_alphabetic() => (c.to_lowercase().collect(), shift),
+            (Some(_), None) => (key.to_string(), false),
+            _ => (key.to_string(), shift),
+        };
+        Chord {
+            key,
+            ctrl,
+            alt,
+            shift,
+            meta,
+        }
+    }
+
+    /// Reads a chord written like `Ctrl+Shift+Z`, `?` or `Ctrl++`.
+    pub fn parse(text: &str) -> Result<Chord, String> {
+        let text = text.trim();
+        // A trailing `+` after a separator (or on its own) is the key.
+        let (modifiers, key) = match text.rfind('+') {
+            _ if text == "+" => ("", "+"),
+            Some(idx) if idx > 0 && text[..idx].ends_with('+') => (&text[..idx - 1], "+"),
+            Some(idx) => (&text[..idx], &text[idx + 1..]),
+            None => ("", text),
+        };
+        if key.is_empty() {
+            return Err(format!("`{}` has no key", text));
+        }
+        let (mut ctrl, mut alt, mut shift, mut meta) = (false, false, false, false);
+        for modifier in modifiers.split('+').filter(|part| !part.is_empty()) {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => ctrl = true,
+                "alt" | "option" => alt = true,
+                "shift" => shift = true,
+                "meta" | "cmd" | "super" => meta = true,
+                other => return Err(format!("unknown modifier `{}` in `{}`", other, text)),
+            }
+        }
+        Ok(Chord::new(key, ctrl, alt, shift, meta))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &(held, name) in &[(self.ctrl, "Ctrl"), (self.alt, "Alt"), (self.shift, "Shift"), (self.meta, "Meta")] {
+            if held {
+                write!(f, "{}+", name)?;
+            }
+        }
+        if self.key.chars().count() == 1 {
+            write!(f, "{}", self.key.to_uppercase())
+        } else {
+            write!(f, "{}", self.key)
+        }
+    }
+}
+
+/// Which action each chord triggers. Later bindings for the same chord
+/// replace earlier ones.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keymap<T> {
+    bindings: Vec<(Chord, T)>,
+}
+
+impl<T> Default for Keymap<T> {
+    fn default() -> Self {
+        Keymap::new()
+    }
+}
+
+impl<T> Keymap<T> {
+    pub fn new() -> Self {
+        Keymap { bindings: Vec::new() }
+    }
+
+    /// Binds `chord` to `action`.
+    pub fn bind(&mut self, chord: Chord, action: T) {
+        self.bindings.retain(|binding| binding.0 != chord);
+        self.bindings.push((chord, action));
+    }
+
+    /// Binds a chord written out as text, for building keymaps from
+    /// literals.
+    ///
+    /// # Panics
+    ///
+    /// If `chord` doesn't parse.
+    pub fn with(mut self, chord: &str, action: T) -> Self {
+        let chord = Chord::parse(chord).unwrap_or_else(|err| panic!("{}", err));
+        self.bind(chord, action);
+        self
+    }
+
+    pub fn get(&self, chord: &Chord) -> Option<&T> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.0 == *chord)
+            .map(|binding| &binding.1)
+    }
+
+    pub fn bindings(&self) -> &[(Chord, T)] {
+        &self.bindings
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+}
+
+/// Whether a key event aimed at an element with this tag name is the user
+/// typing, rather than a shortcut.
+pub fn is_typing(tag: &str, editable: bool) -> bool {
+    let tag = tag.to_uppercase();
+    editable || ["INPUT", "TEXTAREA", "SELECT"].contains(&tag.as_str())
+}
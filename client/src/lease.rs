@@ -0,0 +1,82 @@
+//! Hands out replica ids to the tabs of one browser, so that no two live
+//! tabs count under the same id while the ids themselves outlive a reload.
+//!
+//! Every tab shares the list through localStorage. A tab holds its id for
+//! `LEASE_MS` at a time and keeps renewing it while it runs; ids whose
+//! lease has run out go to the next tab that loads. The list therefore only
+//! grows to the most tabs ever open at once, and each id's tally keeps
+//! counting up instead of a new one starting on every page load.
+
+/// How long a claim lasts without being renewed, in milliseconds. Long
+/// enough to outlast the timer throttling of a tab in the background.
+pub const LEASE_MS: f64 = 5.0 * 60.0 * 1000.0;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Leases {
+    leases: Vec<Lease>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Lease {
+    id: String,
+    /// Made up by the page load holding the id.
+    owner: String,
+    /// Milliseconds since the Unix epoch.
+    until: f64,
+}
+
+impl Leases {
+    /// Claims an id for a page that is just loading: the first one nobody
+    /// holds any more, or `fresh` if every one is taken. Reusing an id is
+    /// only safe here, before the page has read the counters back, since
+    /// they then include everything the last holder saved.
+    pub fn claim(&mut self, owner: &str, now: f64, fresh: String) -> String {
+        match self.leases.iter_mut().find(|lease| lease.until <= now) {
+            Some(lease) => {
+                lease.owner = owner.to_string();
+                lease.until = now + LEASE_MS;
+                lease.id.clone()
+            }
+            None => self.add(owner, now, fresh),
+        }
+    }
+
+    /// Claims `fresh`, a new id, for a page that has lost the one it held.
+    pub fn add(&mut self, owner: &str, now: f64, fresh: String) -> String {
+        self.leases.push(Lease {
+            id: fresh.clone(),
+            owner: owner.to_string(),
+            until: now + LEASE_MS,
+        });
+        fresh
+    }
+
+    /// Extends `owner`'s hold on `id`. Returns `false` if another page has
+    /// claimed it since, in which case `owner` has to stop counting under it.
+    /// A lease that ran out but nobody took is simply picked up again.
+    pub fn renew(&mut self, id: &str, owner: &str, now: f64) -> bool {
+        match self.leases.iter_mut().find(|lease| lease.id == id) {
+            Some(ref mut lease) if lease.owner == owner => {
+                lease.until = now + LEASE_MS;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Lets the next page to load have `id` straight away.
+    pub fn release(&mut self, id: &str, owner: &str) {
+        if let Some(lease) = self
+            .leases
+            .iter_mut()
+            .find(|lease| lease.id == id && lease.owner == owner)
+        {
+            lease.until = 0.0;
+        }
+    }
+
+    /// Whether `owner` holds `id`.
+    pub fn holds(&self, id: &str, owner: &str) -> bool {
+        self.leases.iter().any(|lease| lease.id == id && lease.owner == owner)
+    }
+}
@@ -1,68 +1,84 @@
+#[cfg(target_arch = "wasm32")]
+extern crate failure;
+#[cfg(target_arch = "wasm32")]
 #[macro_use]
 extern crate stdweb;
+#[cfg(target_arch = "wasm32")]
 #[macro_use]
 extern crate yew;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate shared;
 
-use stdweb::web::Date;
-use yew::prelude::*;
-use yew::services::ConsoleService;
-
-pub struct Model {
-    console: ConsoleService,
-    value: i64,
-}
-
-pub enum Msg {
-    Increment,
-    Decrement,
-    Bulk(Vec<Msg>),
-}
-
-impl Component for Model {
-    type Message = Msg;
-    type Properties = ();
-
-    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
-        Model {
-            console: ConsoleService::new(),
-            value: 0,
-        }
-    }
+pub mod chart;
+pub mod counter;
+pub mod effects;
+pub mod entry;
+pub mod format;
+mod history;
+pub mod journal;
+pub mod keys;
+pub mod lease;
+pub mod logging;
+pub mod paint;
+pub mod route;
+pub mod scene;
+pub mod settings;
+pub mod share;
+pub mod svg;
+pub mod theme;
+pub mod timeline;
 
-    fn update(&mut self, msg: Self::Message) -> ShouldRender {
-        match msg {
-            Msg::Increment => {
-                self.value = self.value + 1;
-                self.console.log("plus one");
-            }
-            Msg::Decrement => {
-                self.value = self.value - 5;
-                self.console.log("minus one");
-                js! {
-                    alert("hi from js");
-                }
-            }
-            Msg::Bulk(list) => for msg in list {
-                self.update(msg);
-                self.console.log("Bulk action");
-            },
-        }
-        true
-    }
-}
+#[cfg(target_arch = "wasm32")]
+pub mod activity;
+#[cfg(target_arch = "wasm32")]
+pub mod canvas;
+#[cfg(target_arch = "wasm32")]
+pub mod counter_list;
+#[cfg(all(target_arch = "wasm32", feature = "debugger"))]
+pub mod debugger;
+#[cfg(target_arch = "wasm32")]
+mod download;
+#[cfg(target_arch = "wasm32")]
+mod keyboard;
+#[cfg(target_arch = "wasm32")]
+pub mod link;
+#[cfg(target_arch = "wasm32")]
+pub mod modal;
+#[cfg(target_arch = "wasm32")]
+mod model;
+#[cfg(target_arch = "wasm32")]
+pub mod pages;
+#[cfg(target_arch = "wasm32")]
+mod replica;
+#[cfg(target_arch = "wasm32")]
+pub mod router;
+#[cfg(target_arch = "wasm32")]
+pub mod scene_canvas;
+#[cfg(target_arch = "wasm32")]
+mod storage;
+#[cfg(target_arch = "wasm32")]
+pub mod settings_page;
+#[cfg(target_arch = "wasm32")]
+pub mod sync;
+#[cfg(target_arch = "wasm32")]
+pub mod timestamp;
 
-impl Renderable<Model> for Model {
-    fn view(&self) -> Html<Self> {
-        html! {
-            <div>
-                <nav class="menu",>
-                    <button onclick=|_| Msg::Increment,>{ "Increment" }</button>
-                    <button onclick=|_| Msg::Decrement,>{ "Decrement" }</button>
-                    <button onclick=|_| Msg::Bulk(vec![Msg::Increment, Msg::Increment]),>{ "Increment Twice" }</button>
-                </nav>
-                <p>{ self.value }</p>
-                <p>{ Date::new().to_string() }</p>
-            </div>
-        }
-    }
-}
+#[cfg(target_arch = "wasm32")]
+pub use canvas::Canvas;
+#[cfg(target_arch = "wasm32")]
+pub use counter_list::CounterList;
+#[cfg(target_arch = "wasm32")]
+pub use link::Link;
+#[cfg(target_arch = "wasm32")]
+pub use modal::Modal;
+#[cfg(target_arch = "wasm32")]
+pub use model::{Model, Msg, Props, Shortcut};
+#[cfg(target_arch = "wasm32")]
+pub use pages::Pages;
+#[cfg(target_arch = "wasm32")]
+pub use scene_canvas::SceneCanvas;
+#[cfg(target_arch = "wasm32")]
+pub use timestamp::Timestamp;
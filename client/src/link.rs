@@ -0,0 +1,83 @@
+//! An in-app link that navigates without reloading the page.
+
+use stdweb::traits::IEvent;
+use yew::prelude::*;
+
+use route::Route;
+use router;
+
+pub struct Link {
+    props: Props,
+}
+
+pub enum Msg {
+    Clicked,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Props {
+    pub route: Route,
+    pub label: String,
+    /// Added to the link's `class`, alongside `active` when `route` is
+    /// `current`.
+    pub class: String,
+    /// The page being shown, if known.
+    pub current: Option<Route>,
+    /// Told about the click instead of the link navigating by itself.
+    pub onnavigate: Option<Callback<Route>>,
+}
+
+impl Default for Props {
+    fn default() -> Self {
+        Props {
+            route: Route::Counters,
+            label: String::new(),
+            class: String::new(),
+            current: None,
+            onnavigate: None,
+        }
+    }
+}
+
+impl Component for Link {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Link { props }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Clicked => match self.props.onnavigate {
+                Some(ref callback) => callback.emit(self.props.route.clone()),
+                None => router::push(&self.props.route),
+            },
+        }
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+}
+
+impl Renderable<Link> for Link {
+    fn view(&self) -> Html<Self> {
+        let mut class = self.props.class.clone();
+        if self.props.current.as_ref() == Some(&self.props.route) {
+            class.push_str(" active");
+        }
+        // Keep a real href so opening in a new tab and copying the link
+        // still work.
+        html! {
+            <a class=class.trim(), href=self.props.route.href(), onclick=|event| {
+                event.prevent_default();
+                Msg::Clicked
+            },>
+                { &self.props.label }
+            </a>
+        }
+    }
+}
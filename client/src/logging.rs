@@ -0,0 +1,221 @@
+//! Leveled, structured logging with pluggable sinks.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Level {
+    pub const ALL: [Level; 5] = [Level::Trace, Level::Debug, Level::Info, Level::Warn, Level::Error];
+}
+
+/// Reads the names `Display` writes.
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        for &level in &Level::ALL {
+            if level.to_string() == text {
+                return Ok(level);
+            }
+        }
+        Err(format!("unknown log level `{}`", text))
+    }
+}
+
+/// A key-value pair attached to a record.
+pub type Field = (&'static str, String);
+
+/// Builds a `Field` from anything printable.
+pub fn field<V: Display>(key: &'static str, value: V) -> Field {
+    (key, value.to_string())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub level: Level,
+    /// The component or module the record came from.
+    pub target: &'static str,
+    pub message: String,
+    pub fields: Vec<Field>,
+}
+
+impl Record {
+    /// Looks up the value of the field named `key`.
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|field| field.0 == key)
+            .map(|field| field.1.as_str())
+    }
+}
+
+/// `[INFO counter] applied kind=Increment old=0 new=1`
+impl Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{} {}] {}", self.level, self.target, self.message)?;
+        for &(key, ref value) in &self.fields {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where records end up once they pass the level filter.
+pub trait Sink {
+    fn write(&mut self, record: &Record);
+}
+
+/// Discards everything.
+pub struct NoopSink;
+
+impl Sink for NoopSink {
+    fn write(&mut self, _: &Record) {}
+}
+
+/// Keeps the most recent records in memory.
+pub struct RingBuffer {
+    records: VecDeque<Record>,
+    capacity: usize,
+}
+
+impl RingBuffer {
+    pub fn new(capacity: usize) -> Self {
+        RingBuffer {
+            records: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// The retained records, oldest first.
+    pub fn records(&self) -> &VecDeque<Record> {
+        &self.records
+    }
+}
+
+impl Sink for RingBuffer {
+    fn write(&mut self, record: &Record) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record.clone());
+    }
+}
+
+/// Filters records by level and forwards them to a sink.
+pub struct Logger<S> {
+    target: &'static str,
+    level: Level,
+    sink: S,
+}
+
+impl<S: Sink> Logger<S> {
+    pub fn new(target: &'static str, level: Level, sink: S) -> Self {
+        Logger {
+            target,
+            level,
+            sink,
+        }
+    }
+
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Changes the minimum level that reaches the sink.
+    pub fn set_level(&mut self, level: Level) {
+        self.level = level;
+    }
+
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    pub fn enabled(&self, level: Level) -> bool {
+        level >= self.level
+    }
+
+    pub fn log(&mut self, level: Level, message: &str, fields: Vec<Field>) {
+        if !self.enabled(level) {
+            return;
+        }
+        let record = Record {
+            level,
+            target: self.target,
+            message: message.to_string(),
+            fields,
+        };
+        self.sink.write(&record);
+    }
+
+    pub fn debug(&mut self, message: &str, fields: Vec<Field>) {
+        self.log(Level::Debug, message, fields);
+    }
+
+    pub fn info(&mut self, message: &str, fields: Vec<Field>) {
+        self.log(Level::Info, message, fields);
+    }
+
+    pub fn warn(&mut self, message: &str, fields: Vec<Field>) {
+        self.log(Level::Warn, message, fields);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use self::browser::ConsoleSink;
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use yew::services::ConsoleService;
+
+    use super::{Level, Record, Sink};
+
+    /// Writes records to the browser console at the matching level.
+    pub struct ConsoleSink {
+        console: ConsoleService,
+    }
+
+    impl ConsoleSink {
+        pub fn new() -> Self {
+            ConsoleSink {
+                console: ConsoleService::new(),
+            }
+        }
+    }
+
+    impl Sink for ConsoleSink {
+        fn write(&mut self, record: &Record) {
+            let line = record.to_string();
+            match record.level {
+                Level::Trace | Level::Debug => self.console.debug(&line),
+                Level::Info => self.console.info(&line),
+                Level::Warn => self.console.warn(&line),
+                Level::Error => self.console.error(&line),
+            }
+        }
+    }
+}
@@ -1,11 +1,37 @@
+#[cfg(target_arch = "wasm32")]
 extern crate client;
+#[cfg(target_arch = "wasm32")]
+extern crate stdweb;
+#[cfg(target_arch = "wasm32")]
 extern crate yew;
 
-use client::Model;
-use yew::prelude::*;
-
+#[cfg(target_arch = "wasm32")]
 fn main() {
+    use client::counter::Config;
+    use client::pages::Msg;
+    use client::theme;
+    use client::Pages;
+    use stdweb::web::document;
+    use yew::prelude::*;
+
     yew::initialize();
-    App::<Model>::new().mount_to_body();
+    theme::install();
+    let mut pages = App::<Pages>::new().mount_to_body();
+    // Tweak the counters' starting value, step sizes and bounds here. The
+    // counters wait for this before creating the first one. Step sizes are
+    // only defaults: once the user saves settings of their own, those win.
+    pages.send_message(Msg::Configure(Config::default()));
+    // Follow the `server` crate's sync endpoint on the host we were served
+    // from. Send `None` instead to keep every counter local.
+    let host = document()
+        .location()
+        .and_then(|location| location.hostname().ok())
+        .unwrap_or_else(|| "127.0.0.1".into());
+    pages.send_message(Msg::SyncWith(Some(format!("ws://{}:8001", host))));
     yew::run_loop();
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    eprintln!("the client only runs in the browser; try `cargo web start`");
+}
@@ -0,0 +1,77 @@
+//! A reusable in-page dialog with confirm and cancel actions.
+
+use yew::prelude::*;
+
+pub struct Modal {
+    props: Props,
+}
+
+pub enum Msg {
+    Confirm,
+    Cancel,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Props {
+    pub title: String,
+    pub body: String,
+    pub confirm_label: String,
+    pub cancel_label: String,
+    pub onconfirm: Option<Callback<()>>,
+    pub oncancel: Option<Callback<()>>,
+}
+
+impl Default for Props {
+    fn default() -> Self {
+        Props {
+            title: String::new(),
+            body: String::new(),
+            confirm_label: "OK".into(),
+            cancel_label: "Cancel".into(),
+            onconfirm: None,
+            oncancel: None,
+        }
+    }
+}
+
+impl Component for Modal {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Modal { props }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        let callback = match msg {
+            Msg::Confirm => &self.props.onconfirm,
+            Msg::Cancel => &self.props.oncancel,
+        };
+        if let Some(ref callback) = *callback {
+            callback.emit(());
+        }
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+}
+
+impl Renderable<Modal> for Modal {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <div class="modal-backdrop",>
+                <div class="modal", role="dialog",>
+                    <h2 class="modal-title",>{ &self.props.title }</h2>
+                    <p class="modal-body",>{ &self.props.body }</p>
+                    <div class="modal-actions",>
+                        <button onclick=|_| Msg::Cancel,>{ &self.props.cancel_label }</button>
+                        <button onclick=|_| Msg::Confirm,>{ &self.props.confirm_label }</button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
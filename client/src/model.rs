@@ -0,0 +1,1051 @@
+use std::time::Duration;
+
+use serde_json::{self, Value};
+use stdweb::traits::IEvent;
+use yew::prelude::*;
+use yew::services::reader::{File, FileData, ReaderService, ReaderTask};
+
+use canvas::{self, Canvas};
+use chart::{LineChart, Point};
+use counter::{Command, Config, Counter, PnCounter, State, Total};
+#[cfg(feature = "debugger")]
+use counter::Snapshot;
+#[cfg(feature = "debugger")]
+use debugger;
+use download;
+use effects::{BrowserClock, Clock};
+use entry;
+use shared::ClientMessage;
+use format::TimeFormat;
+use journal::{Entry, Event, Journal};
+use keyboard::KeyboardListener;
+use keys::{Chord, Keymap};
+use logging::{field, ConsoleSink, Level, Logger};
+#[cfg(feature = "debugger")]
+use logging::NoopSink;
+use modal::Modal;
+use paint::Align;
+use replica;
+use router;
+use scene::{Node, Transform};
+use scene_canvas::SceneCanvas;
+use share::{self, Params};
+use storage::{self, Store, Upgrade};
+use svg;
+use sync::{self, Status, SyncClient};
+use theme::Theme;
+#[cfg(feature = "debugger")]
+use timeline::{Action, Timeline};
+use timestamp::Timestamp;
+
+/// How many past values the chart keeps.
+const CHART_LIMIT: usize = 200;
+
+/// Roughly how many journal entries are kept across reloads.
+const JOURNAL_LIMIT: usize = 500;
+/// How many entries go by between the snapshots the saved journal is
+/// trimmed at.
+const SNAPSHOT_EVERY: usize = 50;
+
+/// Size of the drawn controls.
+const CONTROLS_WIDTH: f64 = 480.0;
+const CONTROLS_HEIGHT: f64 = 64.0;
+/// Size of the chart, on screen and in exports.
+const CHART_WIDTH: u32 = 480;
+const CHART_HEIGHT: u32 = 200;
+
+type ValueChart = Canvas<LineChart>;
+type Controls = SceneCanvas<Control>;
+
+/// Things a keyboard shortcut can do.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shortcut {
+    Increment,
+    Decrement,
+    Undo,
+    Redo,
+    /// Shows or hides the list of shortcuts.
+    ToggleHelp,
+    CloseHelp,
+}
+
+impl Shortcut {
+    /// The standard bindings.
+    pub fn keymap() -> Keymap<Shortcut> {
+        Keymap::new()
+            .with("+", Shortcut::Increment)
+            .with("=", Shortcut::Increment)
+            .with("-", Shortcut::Decrement)
+            .with("u", Shortcut::Undo)
+            .with("Ctrl+Z", Shortcut::Undo)
+            .with("Ctrl+Shift+Z", Shortcut::Redo)
+            .with("Ctrl+Y", Shortcut::Redo)
+            .with("?", Shortcut::ToggleHelp)
+            .with("Escape", Shortcut::CloseHelp)
+    }
+
+    /// What it does, for the help overlay.
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Shortcut::Increment => "Increment",
+            Shortcut::Decrement => "Decrement",
+            Shortcut::Undo => "Undo",
+            Shortcut::Redo => "Redo",
+            Shortcut::ToggleHelp => "Show or hide this help",
+            Shortcut::CloseHelp => "Close this help",
+        }
+    }
+
+    fn msg(&self, help: bool) -> Msg {
+        match *self {
+            Shortcut::Increment => Msg::Command(Command::Increment),
+            Shortcut::Decrement => Msg::Command(Command::Decrement),
+            Shortcut::Undo => Msg::Undo,
+            Shortcut::Redo => Msg::Redo,
+            Shortcut::ToggleHelp => Msg::ShowHelp(!help),
+            Shortcut::CloseHelp => Msg::ShowHelp(false),
+        }
+    }
+}
+
+/// File types the chart can be saved as.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+/// The clickable parts of the drawn controls.
+#[derive(Clone, Copy, PartialEq)]
+enum Control {
+    Increment,
+    Decrement,
+}
+
+/// How many messages the debugger panel remembers.
+#[cfg(feature = "debugger")]
+const TIMELINE_LIMIT: usize = 500;
+
+pub struct Model {
+    link: ComponentLink<Model>,
+    props: Props,
+    store: Store,
+    counter: Counter<ConsoleSink>,
+    /// A `Decrement` is waiting on the confirmation dialog.
+    confirming: bool,
+    sync: Option<SyncClient>,
+    /// Everything that changed the counter, including in earlier visits.
+    journal: Journal<BrowserClock>,
+    /// Where the journal is kept. Tabs on the same counter share it, so
+    /// the last one to change anything wins.
+    journal_store: Store,
+    reader: ReaderService,
+    /// A journal file being read for import.
+    importing: Option<ReaderTask>,
+    /// Why the last import failed.
+    import_error: Option<String>,
+    /// What's typed in the value entry box.
+    entry: String,
+    /// What's wrong with `entry`, if anything.
+    entry_error: Option<String>,
+    /// Why the last command was refused by the overflow policy.
+    overflow_error: Option<String>,
+    /// Settings the URL overrides, when `share_url` is on. Its `value` is
+    /// only offered, until applied or dismissed: applying it changes the
+    /// counter for everyone it syncs with.
+    params: Params,
+    /// Why the URL's settings were ignored.
+    link_error: Option<String>,
+    /// Recent values, for the chart under the counter.
+    chart: LineChart,
+    /// Reports key presses while there are shortcuts to look them up in.
+    keyboard: Option<KeyboardListener>,
+    /// The shortcut help overlay is open.
+    help: bool,
+    /// Every message handled and the state it left, for the debugger panel.
+    #[cfg(feature = "debugger")]
+    timeline: Timeline<Snapshot>,
+}
+
+pub enum Msg {
+    Command(Command),
+    Undo,
+    Redo,
+    /// Moves the counter straight to a value.
+    Set(Total),
+    /// The value entry box was edited.
+    EditEntry(String),
+    /// The value entry form was submitted.
+    SubmitEntry,
+    /// Moves the counter to the value the link offered.
+    ApplyLink,
+    /// Forgets the value the link offered.
+    DismissLink,
+    /// The answer from the "are you sure?" dialog shown before a `Decrement`.
+    ConfirmDecrement(bool),
+    /// Replaces the counter's configuration, e.g. from `main` after mounting.
+    Configure(Config),
+    /// Something happened on the sync connection.
+    Sync(sync::Event),
+    /// Downloads the journal as JSON Lines.
+    ExportJournal,
+    /// Files picked for import; only the first is read.
+    ImportJournal(Vec<File>),
+    /// A picked journal file finished reading.
+    JournalRead(FileData),
+    /// Downloads the chart as an image.
+    SaveChart(ImageFormat),
+    /// A key was pressed outside of any form field.
+    Key(Chord),
+    /// Opens or closes the shortcut help overlay.
+    ShowHelp(bool),
+    /// Navigates the debugger panel.
+    #[cfg(feature = "debugger")]
+    Debug(Action),
+}
+
+#[cfg(feature = "debugger")]
+impl Msg {
+    /// A short description for the debugger panel.
+    fn label(&self) -> String {
+        match *self {
+            Msg::Command(ref command) => format!("Command({:?})", command),
+            Msg::Undo => "Undo".into(),
+            Msg::Redo => "Redo".into(),
+            Msg::Set(ref value) => format!("Set({})", value),
+            Msg::EditEntry(ref text) => format!("EditEntry({:?})", text),
+            Msg::SubmitEntry => "SubmitEntry".into(),
+            Msg::ApplyLink => "ApplyLink".into(),
+            Msg::DismissLink => "DismissLink".into(),
+            Msg::ConfirmDecrement(confirmed) => format!("ConfirmDecrement({})", confirmed),
+            Msg::Configure(_) => "Configure".into(),
+            Msg::Sync(ref event) => {
+                let kind = match *event {
+                    sync::Event::Received(_) => "Received",
+                    sync::Event::Opened => "Opened",
+                    sync::Event::Lost => "Lost",
+                    sync::Event::Retry => "Retry",
+                };
+                format!("Sync({})", kind)
+            }
+            Msg::ExportJournal => "ExportJournal".into(),
+            Msg::ImportJournal(ref files) => format!("ImportJournal({} file(s))", files.len()),
+            Msg::JournalRead(ref data) => format!("JournalRead({})", data.name),
+            Msg::SaveChart(format) => format!("SaveChart({:?})", format),
+            Msg::Key(ref chord) => format!("Key({})", chord),
+            Msg::ShowHelp(show) => format!("ShowHelp({})", show),
+            Msg::Debug(action) => format!("Debug({:?})", action),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Props {
+    pub config: Config,
+    /// Whether a `Decrement` asks for confirmation first.
+    pub confirm_decrement: bool,
+    /// How often the clock under the counter refreshes.
+    pub clock_refresh: Duration,
+    /// How the clock under the counter is written out.
+    pub clock_format: TimeFormat,
+    /// The least severe log level that reaches the console.
+    pub log_level: Level,
+    /// State owned by a parent component. When set, the counter starts
+    /// from it, follows its changes, and isn't persisted on its own.
+    pub value: Option<State>,
+    /// Called with the new state after every change.
+    pub onchange: Option<Callback<State>>,
+    /// WebSocket endpoint of the sync server, e.g. `ws://localhost:8001`.
+    /// `None` keeps the counter local.
+    pub sync_url: Option<String>,
+    /// Name of the server-side counter this one follows.
+    pub sync_channel: String,
+    /// Keyboard shortcuts. Empty turns them off, e.g. for all but one of
+    /// several counters on a page.
+    pub shortcuts: Keymap<Shortcut>,
+    /// Takes the configuration from the URL's query when created, offers
+    /// its value to apply, and keeps the query up to date from then on, so
+    /// the address can be shared. At most one counter on a page should.
+    pub share_url: bool,
+    /// Colours for the drawn controls and the chart.
+    pub theme: Theme,
+}
+
+impl Default for Props {
+    fn default() -> Self {
+        Props {
+            config: Config::default(),
+            confirm_decrement: true,
+            clock_refresh: Duration::from_secs(1),
+            clock_format: TimeFormat::LocaleShort,
+            log_level: Level::Info,
+            value: None,
+            onchange: None,
+            sync_url: None,
+            sync_channel: "counter".into(),
+            shortcuts: Shortcut::keymap(),
+            share_url: false,
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl Component for Model {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, mut link: ComponentLink<Self>) -> Self {
+        let mut store = Store::new(storage::COUNTER_KEY);
+        let replica = replica::id();
+        let state = match props.value {
+            Some(ref state) => Some(state.clone()),
+            None => store.load(),
+        };
+        let state = state.unwrap_or_else(|| State::initial(props.config.initial));
+        let mut logger = Logger::new("counter", props.log_level, ConsoleSink::new());
+        let (params, link_error) = read_link(&props, &mut logger);
+        let mut counter = Counter::new(params.configure(&props.config), replica, state, logger);
+        let sync = props
+            .sync_url
+            .clone()
+            .map(|url| SyncClient::new(url, props.sync_channel.clone(), &mut link));
+        let mut journal_store = Store::new(&storage::journal_key(&props.sync_channel));
+        let mut journal = Journal::new(BrowserClock);
+        if let Err(err) = journal.switch(journal_store.load()) {
+            counter
+                .logger_mut()
+                .warn("dropped the saved journal", vec![field("error", &err)]);
+        }
+        let theme = props.theme.clone();
+        let mut model = Model {
+            link,
+            props,
+            store,
+            counter,
+            confirming: false,
+            sync,
+            journal,
+            journal_store,
+            reader: ReaderService::new(),
+            importing: None,
+            import_error: None,
+            entry: String::new(),
+            entry_error: None,
+            overflow_error: None,
+            params,
+            link_error,
+            chart: LineChart {
+                points: Vec::new(),
+                theme,
+            },
+            keyboard: None,
+            help: false,
+            #[cfg(feature = "debugger")]
+            timeline: Timeline::new(TIMELINE_LIMIT),
+        };
+        // Replays of this visit start here, whatever the saved entries say.
+        let snapshot = model.counter.snapshot();
+        model.record(Event::Snapshot(snapshot));
+        model.listen();
+        model.sample();
+        if model.params.value.as_ref() == Some(&model.counter.total()) {
+            model.params.value = None;
+        }
+        // Also clears out a query we refused.
+        model.share();
+        model
+    }
+
+    #[cfg(not(feature = "debugger"))]
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        self.handle(msg)
+    }
+
+    #[cfg(feature = "debugger")]
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        if let Msg::Debug(action) = msg {
+            self.timeline.handle(action);
+            return true;
+        }
+        let label = msg.label();
+        let before = self.counter.snapshot();
+        let bulk = match msg {
+            Msg::Command(Command::Bulk(ref list)) => Some(list.clone()),
+            _ => None,
+        };
+        self.handle(msg);
+        self.timeline.record(label, 0, self.counter.snapshot());
+        if let Some(list) = bulk {
+            // Bulk children run inside the counter, so work out the state
+            // after each one on a scratch copy.
+            let logger = Logger::new("debugger", Level::Error, NoopSink);
+            let mut scratch = Counter::new(
+                before.config.clone(),
+                self.counter.replica().to_string(),
+                before.state.clone(),
+                logger,
+            );
+            scratch.restore(before);
+            trace(&mut self.timeline, &mut scratch, &list, 1);
+        }
+        // The panel lists every message, even ones that changed nothing.
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        // Everything recorded from here on belongs in the new channel's
+        // journal, so load that one before recording anything.
+        let switched = props.sync_channel != self.props.sync_channel;
+        let relink = switched || props.share_url != self.props.share_url;
+        if switched {
+            self.open_journal(&props.sync_channel);
+        }
+        match props.value {
+            Some(ref state) if state != self.counter.state() => {
+                // The parent now shows a different counter in our slot
+                // (e.g. after a reorder), so our history no longer applies.
+                self.record(Event::Reset(state.clone()));
+                self.counter.reset(state.clone());
+                self.chart.points.clear();
+                self.sample();
+                // The link offered its value for the counter that was here.
+                self.params.value = None;
+            }
+            _ if switched => {
+                // Replays of the new journal pick up from here.
+                let snapshot = self.counter.snapshot();
+                self.record(Event::Snapshot(snapshot));
+            }
+            _ => {}
+        }
+        self.counter.logger_mut().set_level(props.log_level);
+        self.chart.theme = props.theme.clone();
+        if props.sync_url != self.props.sync_url {
+            self.sync = props
+                .sync_url
+                .clone()
+                .map(|url| SyncClient::new(url, props.sync_channel.clone(), &mut self.link));
+        } else if props.sync_channel != self.props.sync_channel {
+            if let Some(ref mut sync) = self.sync {
+                sync.follow(props.sync_channel.clone());
+            }
+        }
+        self.props = props;
+        self.listen();
+        if relink {
+            // Another counter, so the URL is another counter's link.
+            let (params, link_error) = read_link(&self.props, self.counter.logger_mut());
+            self.params = params;
+            self.link_error = link_error;
+            if self.params.value.as_ref() == Some(&self.counter.total()) {
+                self.params.value = None;
+            }
+        }
+        let config = self.config();
+        if &config != self.counter.config() {
+            self.record(Event::Configure(config.clone()));
+        }
+        let before = self.counter.state().counter.clone();
+        if self.counter.configure(config) {
+            self.publish(&before);
+            self.save();
+        }
+        self.share();
+        true
+    }
+}
+
+impl Model {
+    /// Handles a message for `update`. Returns whether to re-render.
+    fn handle(&mut self, msg: Msg) -> ShouldRender {
+        let changed = match msg {
+            Msg::Command(Command::Decrement) if self.props.confirm_decrement => {
+                self.confirming = true;
+                return true;
+            }
+            Msg::ApplyLink => {
+                if let Some(value) = self.params.value.take() {
+                    self.handle(Msg::Set(value));
+                }
+                self.share();
+                return true;
+            }
+            Msg::DismissLink => {
+                self.params.value = None;
+                self.share();
+                return true;
+            }
+            Msg::ConfirmDecrement(confirmed) => {
+                self.confirming = false;
+                if confirmed {
+                    self.apply(Command::Decrement);
+                }
+                return true;
+            }
+            Msg::Command(command) => return self.apply(command),
+            Msg::Undo => {
+                // Journal only steps that happen. Checked up front: the entry
+                // has to go in before the step, like any other.
+                if !self.counter.can_undo() {
+                    return false;
+                }
+                self.record(Event::Undo);
+                let before = self.counter.state().counter.clone();
+                self.counter.undo() && self.publish(&before)
+            }
+            Msg::Redo => {
+                if !self.counter.can_redo() {
+                    return false;
+                }
+                self.record(Event::Redo);
+                let before = self.counter.state().counter.clone();
+                self.counter.redo() && self.publish(&before)
+            }
+            Msg::Set(value) => {
+                self.record(Event::Set(value));
+                let before = self.counter.state().counter.clone();
+                self.counter.set(value) && self.publish(&before)
+            }
+            Msg::EditEntry(text) => {
+                self.entry_error = if text.trim().is_empty() {
+                    None
+                } else {
+                    self.parse_entry(&text).err()
+                };
+                self.entry = text;
+                return true;
+            }
+            Msg::SubmitEntry => {
+                return match self.parse_entry(&self.entry) {
+                    Ok(value) => {
+                        self.entry.clear();
+                        self.entry_error = None;
+                        self.handle(Msg::Set(value));
+                        true
+                    }
+                    Err(err) => {
+                        self.entry_error = Some(err);
+                        true
+                    }
+                };
+            }
+            Msg::Configure(config) => {
+                self.record(Event::Configure(config.clone()));
+                let before = self.counter.state().counter.clone();
+                self.counter.configure(config) && self.publish(&before)
+            }
+            Msg::Sync(event) => {
+                let remote = match self.sync {
+                    Some(ref mut sync) => sync.handle(event, &self.counter.state().counter),
+                    None => None,
+                };
+                if let Some(state) = remote {
+                    if self.counter.merge(&state) {
+                        self.append(None, Event::Merge(state));
+                        self.save();
+                    }
+                }
+                // The connection status may have changed either way.
+                return true;
+            }
+            Msg::ExportJournal => {
+                let name = format!("{}-journal.jsonl", self.props.sync_channel);
+                download::save(&name, "application/x-ndjson", &self.journal.to_json_lines());
+                return false;
+            }
+            Msg::ImportJournal(files) => {
+                let file = match files.into_iter().next() {
+                    Some(file) => file,
+                    None => return false,
+                };
+                let callback = self.link.send_back(Msg::JournalRead);
+                self.importing = Some(self.reader.read_file(file, callback));
+                return false;
+            }
+            Msg::JournalRead(data) => {
+                self.importing = None;
+                return self.import(&data.content);
+            }
+            Msg::Key(chord) => {
+                return match self.props.shortcuts.get(&chord) {
+                    Some(shortcut) => {
+                        let msg = shortcut.msg(self.help);
+                        self.handle(msg)
+                    }
+                    None => false,
+                };
+            }
+            Msg::ShowHelp(show) => {
+                self.help = show;
+                return true;
+            }
+            Msg::SaveChart(format) => {
+                let name = format!("{}-chart", self.props.sync_channel);
+                match format {
+                    ImageFormat::Png => match canvas::to_png(&self.chart, CHART_WIDTH, CHART_HEIGHT) {
+                        Some(url) => download::save_url(&format!("{}.png", name), &url),
+                        None => self.counter.logger_mut().warn("couldn't encode the chart as PNG", Vec::new()),
+                    },
+                    ImageFormat::Svg => {
+                        let doc = svg::render(&self.chart, f64::from(CHART_WIDTH), f64::from(CHART_HEIGHT));
+                        download::save(&format!("{}.svg", name), "image/svg+xml", &doc);
+                    }
+                }
+                return false;
+            }
+            // `update` deals with these before we get here.
+            #[cfg(feature = "debugger")]
+            Msg::Debug(_) => return false,
+        };
+        if changed {
+            self.save();
+        }
+        changed
+    }
+
+    /// Applies a local command and shares it with the sync server. Shows
+    /// why if the overflow policy refuses it.
+    fn apply(&mut self, command: Command) -> bool {
+        self.record(Event::Command(command.clone()));
+        let before = self.counter.state().counter.clone();
+        match self.counter.try_update(command) {
+            Ok(changed) => {
+                let cleared = self.overflow_error.take().is_some();
+                if !changed {
+                    return cleared;
+                }
+            }
+            Err(err) => {
+                self.overflow_error = Some(err.to_string());
+                return true;
+            }
+        }
+        self.publish(&before);
+        self.save();
+        true
+    }
+
+    /// Starts or stops listening for shortcuts to match the props.
+    fn listen(&mut self) {
+        if self.props.shortcuts.is_empty() {
+            self.keyboard = None;
+            self.help = false;
+        } else if self.keyboard.is_none() {
+            let callback = self.link.send_back(Msg::Key);
+            self.keyboard = Some(KeyboardListener::new(callback));
+        }
+    }
+
+    /// Carries on with the journal saved for `channel`. The current one is
+    /// already saved under its own channel's key.
+    fn open_journal(&mut self, channel: &str) {
+        self.journal_store = Store::new(&storage::journal_key(channel));
+        if let Err(err) = self.journal.switch(self.journal_store.load()) {
+            self.counter
+                .logger_mut()
+                .warn("dropped the saved journal", vec![field("error", &err)]);
+        }
+    }
+
+    /// Journals a change this tab is about to make. Picks up a new replica
+    /// id first if another tab has taken ours over.
+    fn record(&mut self, event: Event) {
+        let replica = replica::id();
+        if replica != self.counter.replica() {
+            self.counter.set_replica(replica.clone());
+        }
+        self.append(Some(&replica), event);
+    }
+
+    /// Journals `event` and saves the journal, trimmed to about
+    /// `JOURNAL_LIMIT` entries. Every `SNAPSHOT_EVERY` entries a snapshot
+    /// goes in first to give the trimming somewhere to cut; the counter
+    /// reflects every earlier entry by now, so it's a faithful one.
+    fn append(&mut self, replica: Option<&str>, event: Event) {
+        if self.journal.since_snapshot() >= SNAPSHOT_EVERY {
+            let snapshot = self.counter.snapshot();
+            self.journal.record(Some(self.counter.replica()), Event::Snapshot(snapshot));
+        }
+        self.journal.record(replica, event);
+        self.journal.trim(JOURNAL_LIMIT);
+        self.journal_store.save(&self.journal.entries());
+    }
+
+    /// Reads the value entry box against the current value and bounds.
+    fn parse_entry(&self, text: &str) -> Result<Total, String> {
+        entry::parse(text, &self.counter.total(), self.counter.config()).map_err(|err| err.to_string())
+    }
+
+    /// Replaces the journal with an exported one and moves the counter to
+    /// the value it ends at. Returns whether to re-render.
+    fn import(&mut self, content: &[u8]) -> bool {
+        let result = match String::from_utf8(content.to_vec()) {
+            Ok(text) => self.journal.import(&text).map_err(|err| err.to_string()),
+            Err(_) => Err("not a text file".to_string()),
+        };
+        if let Err(err) = result {
+            self.counter
+                .logger_mut()
+                .warn("journal import failed", vec![field("error", &err)]);
+            self.import_error = Some(err);
+            return true;
+        }
+        self.import_error = None;
+        // Arrive there as a change of our own. Restoring the imported state
+        // would roll our tallies back, and the next merge would undo that.
+        let target = self.journal.replayed_total(&self.config());
+        self.record(Event::Set(target.clone()));
+        let before = self.counter.state().counter.clone();
+        if self.counter.set(target) {
+            self.publish(&before);
+            self.save();
+        }
+        true
+    }
+
+    /// Sends the sync server whatever changed locally since `before`.
+    /// Always returns `true` so it can be chained.
+    fn publish(&mut self, before: &PnCounter) -> bool {
+        let delta = self.counter.state().counter.delta(before);
+        if let Some(ref mut sync) = self.sync {
+            if !delta.is_empty() {
+                let counter = sync.counter().to_string();
+                sync.send(ClientMessage::Merge { counter, delta });
+            }
+        }
+        true
+    }
+
+    /// The configuration to run with: the props' own, plus whatever the URL
+    /// overrides.
+    fn config(&self) -> Config {
+        self.params.configure(&self.props.config)
+    }
+
+    /// Mirrors the value and configuration into the URL when this counter
+    /// owns it. Replaces the current history entry rather than adding one,
+    /// so every click doesn't become another step back.
+    fn share(&self) {
+        if self.props.share_url {
+            // A value the link offered stays in it until applied or dismissed.
+            let total = self.counter.total();
+            let value = self.params.value.as_ref().unwrap_or(&total);
+            let query = share::encode(value, self.counter.config(), &self.props.config);
+            router::replace_query(&query);
+        }
+    }
+
+    /// Persists the state (unless a parent owns it), reports it upwards and
+    /// updates the URL.
+    fn save(&mut self) {
+        if self.props.value.is_none() {
+            self.store.save(self.counter.state());
+        }
+        if let Some(ref onchange) = self.props.onchange {
+            onchange.emit(self.counter.state().clone());
+        }
+        self.share();
+        self.sample();
+    }
+
+    /// Adds the current value to the chart if it moved.
+    fn sample(&mut self) {
+        let value = self.counter.value();
+        if self.chart.points.last().map(|point| point.value) == Some(value) {
+            return;
+        }
+        self.chart.points.push(Point {
+            at: BrowserClock.now(),
+            value,
+        });
+        if self.chart.points.len() > CHART_LIMIT {
+            self.chart.points.remove(0);
+        }
+    }
+
+    fn view_sync(&self) -> Html<Model> {
+        let sync = match self.sync {
+            Some(ref sync) => sync,
+            None => return html! { <div></div> },
+        };
+        let (class, text) = match sync.status() {
+            Status::Connecting => ("sync sync-connecting", "Connecting…".to_string()),
+            Status::Online => ("sync sync-online", "Online".to_string()),
+            Status::Offline if sync.pending() => (
+                "sync sync-offline",
+                "Offline, changes will sync when back online".to_string(),
+            ),
+            Status::Offline => ("sync sync-offline", "Offline".to_string()),
+        };
+        html! {
+            <span class=class,>{ text }</span>
+        }
+    }
+
+    /// The value on screen: the live one, or a past one the debugger is
+    /// previewing.
+    #[cfg(feature = "debugger")]
+    fn shown_value(&self) -> Total {
+        match self.timeline.shown() {
+            Some(snapshot) => snapshot.state.total(),
+            None => self.counter.total(),
+        }
+    }
+
+    #[cfg(not(feature = "debugger"))]
+    fn shown_value(&self) -> Total {
+        self.counter.total()
+    }
+
+    #[cfg(feature = "debugger")]
+    fn view_debugger(&self) -> Html<Model> {
+        debugger::view(
+            &self.timeline,
+            |snapshot: &Snapshot| snapshot.state.total().to_string(),
+            Msg::Debug,
+        )
+    }
+
+    #[cfg(not(feature = "debugger"))]
+    fn view_debugger(&self) -> Html<Model> {
+        html! { <div></div> }
+    }
+
+    /// The drawn controls: a button either side of a gauge showing where
+    /// the value sits between its bounds.
+    fn controls(&self) -> Node<Control> {
+        let total = self.shown_value();
+        let value = total.saturating_i64();
+        let config = self.counter.config();
+        // Without both bounds, spread the gauge around zero and squash
+        // large values towards the ends.
+        let position = match (config.min, config.max) {
+            (Some(min), Some(max)) if max > min => (value - min) as f64 / (max - min) as f64,
+            _ => 0.5 + 0.5 * value as f64 / (value.abs() as f64 + 10.0),
+        };
+        let (left, right) = (70.0, CONTROLS_WIDTH - 70.0);
+        let marker = left + position.max(0.0).min(1.0) * (right - left);
+        let middle = CONTROLS_HEIGHT / 2.0;
+        let theme = &self.props.theme;
+        let button = |x: f64, label: &str, color: &str, control: Control| {
+            Node::group(vec![
+                Node::circle(0.0, 0.0, 20.0).fill(color).tag(control),
+                Node::text(label, 0.0, 0.0, Align::Center).fill("#fff"),
+            ])
+            .transform(Transform::translate(x, middle))
+        };
+        Node::group(vec![
+            Node::rect(0.0, 0.0, CONTROLS_WIDTH, CONTROLS_HEIGHT).fill(theme.surface),
+            button(30.0, "−", "#c0392b", Control::Decrement),
+            button(CONTROLS_WIDTH - 30.0, "+", "#27ae60", Control::Increment),
+            Node::rect(left, middle + 6.0, right - left, 6.0).fill(theme.border),
+            Node::path(vec![(marker - 6.0, middle + 20.0), (marker, middle + 12.0), (marker + 6.0, middle + 20.0)], true)
+                .fill(theme.text),
+            Node::text(total.to_string(), marker, middle - 8.0, Align::Center).fill(theme.text),
+        ])
+    }
+
+    fn view_overflow(&self) -> Html<Model> {
+        match self.overflow_error {
+            Some(ref err) => html! { <p class="error",>{ format!("Not applied: {}", err) }</p> },
+            None => html! { <div></div> },
+        }
+    }
+
+    fn view_link_value(&self) -> Html<Model> {
+        match self.params.value {
+            Some(ref value) => html! {
+                <p class="link-value",>
+                    { format!("This link shows the counter at {}. ", value) }
+                    <button onclick=|_| Msg::ApplyLink,>{ format!("Set it to {}", value) }</button>
+                    <button onclick=|_| Msg::DismissLink,>{ "Dismiss" }</button>
+                </p>
+            },
+            None => html! { <div></div> },
+        }
+    }
+
+    fn view_link_error(&self) -> Html<Model> {
+        match self.link_error {
+            Some(ref err) => html! { <p class="error",>{ format!("Ignored the link's settings: {}", err) }</p> },
+            None => html! { <div></div> },
+        }
+    }
+
+    fn view_entry(&self) -> Html<Model> {
+        let error = match self.entry_error {
+            Some(ref err) => html! { <span class="error",>{ err }</span> },
+            None => html! { <span></span> },
+        };
+        html! {
+            <form class="value-entry", onsubmit=|event| {
+                event.prevent_default();
+                Msg::SubmitEntry
+            },>
+                <input type="text", value=&self.entry, placeholder="42, +10 or -3", oninput=|e| Msg::EditEntry(e.value),/>
+                <button type="submit",>{ "Set" }</button>
+                { error }
+            </form>
+        }
+    }
+
+    fn view_journal(&self) -> Html<Model> {
+        let error = match self.import_error {
+            Some(ref err) => html! { <p class="error",>{ format!("Import failed: {}", err) }</p> },
+            None => html! { <div></div> },
+        };
+        html! {
+            <div class="journal",>
+                <button onclick=|_| Msg::ExportJournal,>
+                    { format!("Export journal ({} entries)", self.journal.len()) }
+                </button>
+                <label>
+                    { "Import journal " }
+                    <input type="file", accept=".jsonl", onchange=|value| {
+                        let mut files = Vec::new();
+                        if let ChangeData::Files(list) = value {
+                            files.extend(list);
+                        }
+                        Msg::ImportJournal(files)
+                    },/>
+                </label>
+                { error }
+            </div>
+        }
+    }
+
+    fn view_help(&self) -> Html<Model> {
+        if !self.help {
+            return html! { <div></div> };
+        }
+        html! {
+            <div class="modal-backdrop",>
+                <div class="modal", role="dialog",>
+                    <h2 class="modal-title",>{ "Keyboard shortcuts" }</h2>
+                    <dl class="shortcuts",>
+                        { for self.props.shortcuts.bindings().iter().map(|binding| html! {
+                            <div>
+                                <dt><kbd>{ &binding.0 }</kbd></dt>
+                                <dd>{ binding.1.description() }</dd>
+                            </div>
+                        }) }
+                    </dl>
+                    <div class="modal-actions",>
+                        <button onclick=|_| Msg::ShowHelp(false),>{ "Close" }</button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn view_confirm(&self) -> Html<Model> {
+        if !self.confirming {
+            return html! { <div></div> };
+        }
+        let body = format!(
+            "This subtracts {} from the counter.",
+            self.counter.config().decrement
+        );
+        html! {
+            <Modal:
+                title="Decrement?",
+                body=body,
+                confirm_label="Decrement",
+                onconfirm=|_| Msg::ConfirmDecrement(true),
+                oncancel=|_| Msg::ConfirmDecrement(false),
+                />
+        }
+    }
+}
+
+/// The settings in the URL, if `props` shares it, or why they're ignored.
+fn read_link(props: &Props, logger: &mut Logger<ConsoleSink>) -> (Params, Option<String>) {
+    if !props.share_url {
+        return (Params::default(), None);
+    }
+    match share::decode(&router::query(), &props.config) {
+        Ok(params) => (params, None),
+        Err(err) => {
+            logger.warn("ignored the link's settings", vec![field("error", &err)]);
+            (Params::default(), Some(err.to_string()))
+        }
+    }
+}
+
+/// Records the state after each command in `list` on the timeline, nested
+/// `Bulk` children included, by running them on `scratch`.
+#[cfg(feature = "debugger")]
+fn trace(timeline: &mut Timeline<Snapshot>, scratch: &mut Counter<NoopSink>, list: &[Command], depth: usize) {
+    for command in list {
+        let before = scratch.snapshot();
+        scratch.update(command.clone());
+        timeline.record(format!("{:?}", command), depth, scratch.snapshot());
+        if let Command::Bulk(ref inner) = *command {
+            let after = scratch.snapshot();
+            scratch.restore(before);
+            trace(timeline, scratch, inner, depth + 1);
+            scratch.restore(after);
+        }
+    }
+}
+
+/// A standalone counter as saved before schema version 2.
+#[derive(Deserialize)]
+struct StateV1 {
+    value: i64,
+}
+
+impl Upgrade for Vec<Entry> {}
+
+impl Upgrade for State {
+    fn upgrade(version: u32, state: Value) -> Option<Self> {
+        match version {
+            1 => {
+                let old: StateV1 = serde_json::from_value(state).ok()?;
+                Some(State::from_value(&replica::id(), old.value))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Renderable<Model> for Model {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <div>
+                <nav class="menu",>
+                    <button onclick=|_| Msg::Command(Command::Increment),>{ "Increment" }</button>
+                    <button onclick=|_| Msg::Command(Command::Decrement),>{ "Decrement" }</button>
+                    <button onclick=|_| Msg::Command(Command::Bulk(vec![Command::Increment, Command::Increment])),>{ "Increment Twice" }</button>
+                    <button disabled=!self.counter.can_undo(), onclick=|_| Msg::Undo,>{ "Undo" }</button>
+                    <button disabled=!self.counter.can_redo(), onclick=|_| Msg::Redo,>{ "Redo" }</button>
+                    { self.view_sync() }
+                </nav>
+                <p>{ self.shown_value() }</p>
+                { self.view_overflow() }
+                { self.view_link_value() }
+                { self.view_link_error() }
+                { self.view_entry() }
+                <Controls:
+                    width=CONTROLS_WIDTH as u32,
+                    height=CONTROLS_HEIGHT as u32,
+                    scene=self.controls(),
+                    onhit=|control| match control {
+                        Control::Increment => Msg::Command(Command::Increment),
+                        Control::Decrement => Msg::Command(Command::Decrement),
+                    },
+                    />
+                <ValueChart: width=CHART_WIDTH, height=CHART_HEIGHT, drawing=self.chart.clone(),/>
+                <nav class="menu",>
+                    <button onclick=|_| Msg::SaveChart(ImageFormat::Png),>{ "Save chart as PNG" }</button>
+                    <button onclick=|_| Msg::SaveChart(ImageFormat::Svg),>{ "Save chart as SVG" }</button>
+                </nav>
+                <Timestamp: refresh=self.props.clock_refresh, format=self.props.clock_format.clone(),/>
+                { self.view_journal() }
+                { self.view_debugger() }
+                { self.view_confirm() }
+                { self.view_help() }
+            </div>
+        }
+    }
+}
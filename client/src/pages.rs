@@ -0,0 +1,185 @@
+//! The top-level component: a menu of pages and whichever one the URL
+//! points at.
+
+use yew::prelude::*;
+
+use activity::Activity;
+use counter::Config;
+use counter_list::CounterList;
+use link::Link;
+use route::Route;
+use router::{self, Router};
+use settings::Settings;
+use settings_page::SettingsPage;
+use storage::{self, Store};
+use theme::{self, SchemeListener};
+use Shortcut;
+
+pub struct Pages {
+    route: Route,
+    /// Keeps `route` in step with the address bar.
+    _router: Router,
+    /// Passed down to every counter. Counters wait for it, so that new ones
+    /// start from its `initial` value.
+    config: Option<Config>,
+    sync_url: Option<String>,
+    settings: Settings,
+    /// The user has settings of their own, saved now or on an earlier
+    /// visit. Until then, the step sizes follow `config`.
+    customised: bool,
+    settings_store: Store,
+    /// Redraws the counters when the browser switches between light and
+    /// dark, which `theme::SYSTEM` follows.
+    _scheme: SchemeListener,
+}
+
+pub enum Msg {
+    /// A `Link` was clicked.
+    Navigate(Route),
+    /// The address bar changed, e.g. through back or forward.
+    Routed(Route),
+    /// Replaces the configuration every counter runs with.
+    Configure(Config),
+    /// Points every counter at a sync server, or takes them offline.
+    SyncWith(Option<String>),
+    /// The user changed their settings.
+    Settings(Settings),
+    /// The browser switched between light and dark.
+    SchemeChanged,
+}
+
+impl Component for Pages {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, mut link: ComponentLink<Self>) -> Self {
+        let mut settings_store = Store::new(storage::SETTINGS_KEY);
+        let saved: Option<Settings> = settings_store.load();
+        let customised = saved.is_some();
+        let settings = saved.unwrap_or_default();
+        theme::apply(&settings.theme);
+        Pages {
+            route: router::current(),
+            _router: Router::new(link.send_back(Msg::Routed)),
+            _scheme: SchemeListener::new(link.send_back(|_| Msg::SchemeChanged)),
+            config: None,
+            sync_url: None,
+            settings,
+            customised,
+            settings_store,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Navigate(route) => {
+                router::push(&route);
+                self.route = route;
+            }
+            Msg::Routed(route) => {
+                if route == self.route {
+                    return false;
+                }
+                self.route = route;
+            }
+            Msg::Configure(config) => {
+                if !self.customised {
+                    let settings = Settings::for_config(&config);
+                    theme::apply(&settings.theme);
+                    self.settings = settings;
+                }
+                self.config = Some(config);
+            }
+            Msg::SyncWith(url) => self.sync_url = url,
+            Msg::Settings(settings) => {
+                theme::apply(&settings.theme);
+                self.settings_store.save(&settings);
+                self.settings = settings;
+                self.customised = true;
+            }
+            Msg::SchemeChanged => {}
+        }
+        true
+    }
+}
+
+impl Renderable<Pages> for Pages {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <div>
+                <nav class="menu pages",>
+                    { self.view_link(Route::Counters, "Counters") }
+                    { self.view_link(Route::History, "History") }
+                    { self.view_link(Route::Settings, "Settings") }
+                </nav>
+                { self.view_page() }
+            </div>
+        }
+    }
+}
+
+impl Pages {
+    fn view_link(&self, route: Route, label: &str) -> Html<Pages> {
+        html! {
+            <Link:
+                route=route,
+                label=label,
+                current=Some(self.route.clone()),
+                onnavigate=|route| Msg::Navigate(route),
+                />
+        }
+    }
+
+    fn view_counters(&self) -> Html<Pages> {
+        let config = match self.config {
+            Some(ref config) => config.clone(),
+            None => return html! { <p>{ "Loading…" }</p> },
+        };
+        let focus = match self.route {
+            Route::Counter(id) => Some(id),
+            _ => None,
+        };
+        html! {
+            <CounterList:
+                config=config,
+                settings=self.settings.clone(),
+                sync_url=self.sync_url.clone(),
+                focus=focus,
+                theme=theme::current(&self.settings.theme).clone(),
+                />
+        }
+    }
+
+        fn view_page(&self) -> Html<Pages> {
+        match self.route {
+            Route::Counters | Route::Counter(_) => self.view_counters(),
+            Route::History => html! { <Activity:/> },
+            Route::Settings => html! {
+                <div>
+                    <SettingsPage:
+                        settings=self.settings.clone(),
+                        defaults=Settings::for_config(&self.config.clone().unwrap_or_default()),
+                        onchange=|settings| Msg::Settings(settings),
+                        />
+                    <section class="shortcuts",>
+                        <h2>{ "Keyboard shortcuts" }</h2>
+                        <dl class="shortcuts",>
+                            { for Shortcut::keymap().bindings().iter().map(|binding| html! {
+                                <div>
+                                    <dt><kbd>{ &binding.0 }</kbd></dt>
+                                    <dd>{ binding.1.description() }</dd>
+                                </div>
+                            }) }
+                        </dl>
+                    </section>
+                </div>
+            },
+            Route::NotFound(ref path) => html! {
+                <p>
+                    { format!("There's no page at /{}. ", path) }
+                    <Link: route=Route::Counters, label="Back to the counters", onnavigate=|route| Msg::Navigate(route),/>
+                </p>
+            },
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! A small 2D drawing interface, so charts can be drawn onto a `<canvas>`
+//! in the browser and checked against a recording in tests.
+
+/// Where a line of text sits relative to its anchor point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+}
+
+/// Anything that can be drawn on. Coordinates are in CSS pixels from the
+/// top-left corner; colors are CSS color strings.
+pub trait Painter {
+    /// Wipes a rectangle back to transparent.
+    fn clear_rect(&mut self, x: f64, y: f64, width: f64, height: f64);
+    /// Strokes straight segments through `points`.
+    fn polyline(&mut self, points: &[(f64, f64)], color: &str, width: f64);
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: &str);
+    /// Fills the closed shape with corners at `points`.
+    fn fill_polygon(&mut self, points: &[(f64, f64)], color: &str);
+    fn fill_circle(&mut self, x: f64, y: f64, radius: f64, color: &str);
+    fn stroke_circle(&mut self, x: f64, y: f64, radius: f64, color: &str, width: f64);
+    /// Writes `text` with its baseline vertically centered on `y`.
+    fn text(&mut self, text: &str, x: f64, y: f64, align: Align, color: &str);
+    /// Limits drawing to a rectangle until `unclip`.
+    fn clip(&mut self, x: f64, y: f64, width: f64, height: f64);
+    fn unclip(&mut self);
+
+    /// Wipes the whole surface.
+    fn clear(&mut self, width: f64, height: f64) {
+        self.clear_rect(0.0, 0.0, width, height);
+    }
+}
+
+/// Something that knows how to draw itself on a surface of a given size.
+/// `pointer` is where the mouse is over the surface, if it's over it at all.
+pub trait Drawing {
+    fn draw<P: Painter>(&self, painter: &mut P, width: f64, height: f64, pointer: Option<(f64, f64)>);
+}
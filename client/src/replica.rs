@@ -0,0 +1,119 @@
+//! Names this tab as a replica of the counters it shares with the server.
+//!
+//! The name is leased from a list kept in localStorage (see `lease`), so a
+//! reload picks its old name back up instead of starting a new tally, while
+//! two tabs open at the same time never count under the same one.
+
+use std::cell::RefCell;
+
+use effects::{BrowserClock, Clock};
+use lease::Leases;
+use storage::{self, Store, Upgrade};
+
+/// How often a running tab renews its lease, in milliseconds.
+const RENEW_EVERY_MS: u32 = 30 * 1000;
+
+impl Upgrade for Leases {}
+
+struct Replica {
+    id: String,
+    /// Tells this page load's claims apart from other tabs'.
+    owner: String,
+    store: Store,
+}
+
+thread_local! {
+    static REPLICA: RefCell<Option<Replica>> = RefCell::new(None);
+}
+
+/// The id this tab's changes count under. It stays the same for the whole
+/// page load unless another tab takes it over, e.g. after this one slept
+/// through its lease; from then on this tab counts under a new id.
+pub fn id() -> String {
+    let (id, claimed) = REPLICA.with(|replica| {
+        let mut replica = replica.borrow_mut();
+        if let Some(ref mut replica) = *replica {
+            replica.renew();
+            return (replica.id.clone(), false);
+        }
+        let claimed = Replica::claim();
+        let id = claimed.id.clone();
+        *replica = Some(claimed);
+        (id, true)
+    });
+    if claimed {
+        keep_alive();
+    }
+    id
+}
+
+impl Replica {
+    /// Claims an id as the page loads.
+    fn claim() -> Self {
+        let owner = random();
+        let mut store = Store::new(storage::REPLICAS_KEY);
+        let mut leases: Leases = store.load().unwrap_or_default();
+        let now = BrowserClock.now();
+        let mut id = leases.claim(&owner, now, fresh_id());
+        store.save(&leases);
+        // localStorage can't compare-and-swap, so a tab loading at the same
+        // moment may have claimed the same id and saved over ours. Whoever
+        // reads back someone else's claim takes a new id. A race past this
+        // point is still caught by `renew` before the first change counts.
+        let mut leases: Leases = store.load().unwrap_or_default();
+        if !leases.holds(&id, &owner) {
+            id = leases.add(&owner, now, fresh_id());
+            store.save(&leases);
+        }
+        Replica { id, owner, store }
+    }
+
+    /// Extends the lease, or moves to a new id if another tab has taken
+    /// this one over.
+    fn renew(&mut self) {
+        let now = BrowserClock.now();
+        let mut leases: Leases = self.store.load().unwrap_or_default();
+        if !leases.renew(&self.id, &self.owner, now) {
+            self.id = leases.add(&self.owner, now, fresh_id());
+        }
+        self.store.save(&leases);
+    }
+
+    fn release(&mut self) {
+        let mut leases: Leases = self.store.load().unwrap_or_default();
+        leases.release(&self.id, &self.owner);
+        self.store.save(&leases);
+    }
+}
+
+/// Renews the lease while the page runs and gives it up when the page
+/// goes away, so a reload gets the same id back.
+fn keep_alive() {
+    let renew = || {
+        id();
+    };
+    let release = || {
+        REPLICA.with(|replica| {
+            if let Some(ref mut replica) = *replica.borrow_mut() {
+                replica.release();
+            }
+        })
+    };
+    js! { @(no_return)
+        var renew = @{renew};
+        var release = @{release};
+        setInterval(function() { renew(); }, @{RENEW_EVERY_MS});
+        window.addEventListener("pagehide", function() { release(); });
+    }
+}
+
+fn fresh_id() -> String {
+    format!("tab-{}", random())
+}
+
+fn random() -> String {
+    let random = js! {
+        return Math.random().toString(36).slice(2, 10);
+    };
+    random.into_string().unwrap_or_default()
+}
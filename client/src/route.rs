@@ -0,0 +1,64 @@
+//! The app's pages and the URL fragments that lead to them.
+
+use std::fmt;
+
+/// A page of the app.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Route {
+    /// Every counter, at `#/`.
+    Counters,
+    /// A single counter by id, at `#/counters/{id}`.
+    Counter(u32),
+    /// Recent changes across all counters, at `#/history`.
+    History,
+    /// At `#/settings`.
+    Settings,
+    /// Anything else, holding the path that didn't match.
+    NotFound(String),
+}
+
+impl Route {
+    /// Reads a URL fragment such as `#/counters/3`. The leading `#` and any
+    /// stray slashes are optional, and a `?query` is ignored.
+    pub fn parse(hash: &str) -> Route {
+        let path = hash.split('?').next().unwrap_or("");
+        let path = path.trim_start_matches('#').trim_matches('/');
+        let segments: Vec<&str> = path.split('/').collect();
+        match segments[..] {
+            [""] | ["counters"] => Route::Counters,
+            ["counters", id] => match id.parse() {
+                Ok(id) => Route::Counter(id),
+                Err(_) => Route::NotFound(path.to_string()),
+            },
+            ["history"] => Route::History,
+            ["settings"] => Route::Settings,
+            _ => Route::NotFound(path.to_string()),
+        }
+    }
+
+    /// The fragment to link to, `#` included.
+    pub fn href(&self) -> String {
+        format!("#/{}", self)
+    }
+}
+
+/// The part of a URL fragment after `?`, or nothing.
+pub fn query(hash: &str) -> &str {
+    match hash.find('?') {
+        Some(idx) => &hash[idx + 1..],
+        None => "",
+    }
+}
+
+/// Writes the path `parse` reads back, without the leading `#/`.
+impl fmt::Display for Route {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Route::Counters => Ok(()),
+            Route::Counter(id) => write!(f, "counters/{}", id),
+            Route::History => write!(f, "history"),
+            Route::Settings => write!(f, "settings"),
+            Route::NotFound(ref path) => write!(f, "{}", path),
+        }
+    }
+}
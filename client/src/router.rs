@@ -0,0 +1,74 @@
+//! Keeps the current page in the URL fragment, e.g. `#/history`. The
+//! fragment never reaches the server, so deep links work against the static
+//! `cargo web deploy` output without any rewrite rules, and the browser's
+//! own history handles back and forward.
+
+use stdweb::traits::*;
+use stdweb::web::event::HashChangeEvent;
+use stdweb::web::{document, window, EventListenerHandle};
+use yew::callback::Callback;
+
+use route::{self, Route};
+
+/// Reports every change of route, back and forward included. Stops
+/// listening when dropped.
+pub struct Router {
+    handle: Option<EventListenerHandle>,
+}
+
+impl Router {
+    pub fn new(callback: Callback<Route>) -> Self {
+        let handle = window().add_event_listener(move |_: HashChangeEvent| {
+            callback.emit(current());
+        });
+        Router { handle: Some(handle) }
+    }
+}
+
+impl Drop for Router {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.remove();
+        }
+    }
+}
+
+/// The route in the address bar.
+pub fn current() -> Route {
+    Route::parse(&hash())
+}
+
+/// The query in the address bar's fragment, without the `?`.
+pub fn query() -> String {
+    route::query(&hash()).to_string()
+}
+
+/// Swaps the fragment's query for `query`. Neither adds a history entry nor
+/// tells listeners, since the page stays the same.
+pub fn replace_query(query: &str) {
+    let mut url = current().href();
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(query);
+    }
+    js! { @(no_return)
+        history.replaceState(history.state, "", @{url});
+    }
+}
+
+/// Goes to `route`, adding a history entry unless it's already current.
+pub fn push(route: &Route) {
+    let hash = route.href();
+    js! { @(no_return)
+        if (window.location.hash !== @{&hash}) {
+            window.location.hash = @{&hash};
+        }
+    }
+}
+
+fn hash() -> String {
+    document()
+        .location()
+        .and_then(|location| location.hash().ok())
+        .unwrap_or_default()
+}
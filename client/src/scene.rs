@@ -0,0 +1,566 @@
+//! A retained-mode scene graph. Components describe what should be on the
+//! canvas as a tree of `Node`s; a `Retained` scene works out which parts of
+//! the surface changed since the last frame, repaints only those, and maps
+//! pointer positions back to the node under them.
+
+use paint::{Align, Drawing, Painter};
+
+/// Rough text metrics, since the painter can't measure text for us.
+const CHAR_WIDTH: f64 = 7.0;
+const LINE_HEIGHT: f64 = 14.0;
+
+/// Past this many separate dirty regions, one region around all of them is
+/// cheaper to repaint.
+const MAX_REGIONS: usize = 8;
+
+/// An affine transform, mapping `(x, y)` to
+/// `(a * x + c * y + e, b * x + d * y + f)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Transform {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    pub fn translate(x: f64, y: f64) -> Self {
+        Transform {
+            e: x,
+            f: y,
+            ..Transform::identity()
+        }
+    }
+
+    pub fn scale(x: f64, y: f64) -> Self {
+        Transform {
+            a: x,
+            d: y,
+            ..Transform::identity()
+        }
+    }
+
+    /// Rotates clockwise on screen by `radians`.
+    pub fn rotate(radians: f64) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Transform {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Transform::identity()
+        }
+    }
+
+    /// The transform that applies `inner` first and then `self`.
+    pub fn then(&self, inner: &Transform) -> Transform {
+        Transform {
+            a: self.a * inner.a + self.c * inner.b,
+            b: self.b * inner.a + self.d * inner.b,
+            c: self.a * inner.c + self.c * inner.d,
+            d: self.b * inner.c + self.d * inner.d,
+            e: self.a * inner.e + self.c * inner.f + self.e,
+            f: self.b * inner.e + self.d * inner.f + self.f,
+        }
+    }
+
+    pub fn apply(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (self.a * x + self.c * y + self.e, self.b * x + self.d * y + self.f)
+    }
+
+    /// How much lengths grow on average, used for radii and line widths.
+    fn stretch(&self) -> f64 {
+        (self.a * self.d - self.b * self.c).abs().sqrt()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    Circle {
+        x: f64,
+        y: f64,
+        radius: f64,
+    },
+    /// Straight segments through `points`, back to the first if `closed`.
+    Path {
+        points: Vec<(f64, f64)>,
+        closed: bool,
+    },
+    Text {
+        text: String,
+        x: f64,
+        y: f64,
+        align: Align,
+    },
+}
+
+/// How a shape is painted. Text uses `fill` for its color.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Style {
+    pub fill: Option<String>,
+    pub stroke: Option<String>,
+    pub line_width: f64,
+}
+
+/// A piece of the scene. `T` tags shapes that react to the pointer; hit
+/// testing hands the tag back.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node<T> {
+    Shape {
+        shape: Shape,
+        style: Style,
+        tag: Option<T>,
+    },
+    Group {
+        transform: Transform,
+        children: Vec<Node<T>>,
+    },
+}
+
+/// An empty scene.
+impl<T> Default for Node<T> {
+    fn default() -> Self {
+        Node::group(Vec::new())
+    }
+}
+
+impl<T> Node<T> {
+    pub fn shape(shape: Shape) -> Self {
+        Node::Shape {
+            shape,
+            style: Style::default(),
+            tag: None,
+        }
+    }
+
+    pub fn rect(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Node::shape(Shape::Rect {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    pub fn circle(x: f64, y: f64, radius: f64) -> Self {
+        Node::shape(Shape::Circle { x, y, radius })
+    }
+
+    pub fn path(points: Vec<(f64, f64)>, closed: bool) -> Self {
+        Node::shape(Shape::Path { points, closed })
+    }
+
+    pub fn text<S: Into<String>>(text: S, x: f64, y: f64, align: Align) -> Self {
+        Node::shape(Shape::Text {
+            text: text.into(),
+            x,
+            y,
+            align,
+        })
+    }
+
+    pub fn group(children: Vec<Node<T>>) -> Self {
+        Node::Group {
+            transform: Transform::identity(),
+            children,
+        }
+    }
+
+    /// Fills a shape. No effect on groups.
+    pub fn fill(mut self, color: &str) -> Self {
+        if let Node::Shape { ref mut style, .. } = self {
+            style.fill = Some(color.to_string());
+        }
+        self
+    }
+
+    /// Outlines a shape. No effect on groups.
+    pub fn stroke(mut self, color: &str, width: f64) -> Self {
+        if let Node::Shape { ref mut style, .. } = self {
+            style.stroke = Some(color.to_string());
+            style.line_width = width;
+        }
+        self
+    }
+
+    /// Makes a shape answer hit tests with `tag`. No effect on groups.
+    pub fn tag(mut self, value: T) -> Self {
+        if let Node::Shape { ref mut tag, .. } = self {
+            *tag = Some(value);
+        }
+        self
+    }
+
+    /// Moves a group by `transform`, on top of any transform it already
+    /// has. No effect on shapes; wrap them in a group first.
+    pub fn transform(mut self, outer: Transform) -> Self {
+        if let Node::Group { ref mut transform, .. } = self {
+            *transform = outer.then(transform);
+        }
+        self
+    }
+}
+
+/// An axis-aligned rectangle on the surface.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Region {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Region {
+    fn around(points: &[(f64, f64)], margin: f64) -> Region {
+        let (mut left, mut top) = points.first().cloned().unwrap_or((0.0, 0.0));
+        let (mut right, mut bottom) = (left, top);
+        for &(x, y) in points {
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x);
+            bottom = bottom.max(y);
+        }
+        Region {
+            x: left - margin,
+            y: top - margin,
+            width: right - left + 2.0 * margin,
+            height: bottom - top + 2.0 * margin,
+        }
+    }
+
+    pub fn intersects(&self, other: &Region) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+
+    pub fn union(&self, other: &Region) -> Region {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Region {
+            x,
+            y,
+            width: (self.x + self.width).max(other.x + other.width) - x,
+            height: (self.y + self.height).max(other.y + other.height) - y,
+        }
+    }
+}
+
+/// A shape flattened into surface coordinates.
+#[derive(Clone, Debug, PartialEq)]
+enum Figure {
+    Polygon(Vec<(f64, f64)>),
+    Polyline(Vec<(f64, f64)>),
+    Circle { x: f64, y: f64, radius: f64 },
+    Text { text: String, x: f64, y: f64, align: Align },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Item<T> {
+    figure: Figure,
+    style: Style,
+    tag: Option<T>,
+    bounds: Region,
+}
+
+/// A scene as it was last painted.
+pub struct Retained<T> {
+    items: Vec<Item<T>>,
+}
+
+impl<T: Clone + PartialEq> Default for Retained<T> {
+    fn default() -> Self {
+        Retained::new()
+    }
+}
+
+impl<T: Clone + PartialEq> Retained<T> {
+    pub fn new() -> Self {
+        Retained { items: Vec::new() }
+    }
+
+    /// Swaps in `scene` and returns the regions that need repainting: the
+    /// old and new bounds of every shape that moved, changed, appeared or
+    /// went away.
+    pub fn update(&mut self, scene: &Node<T>) -> Vec<Region> {
+        let mut items = Vec::new();
+        flatten(scene, &Transform::identity(), &mut items);
+        let mut dirty = Vec::new();
+        for idx in 0..items.len().max(self.items.len()) {
+            let (old, new) = (self.items.get(idx), items.get(idx));
+            if old == new {
+                continue;
+            }
+            dirty.extend(old.map(|item| item.bounds));
+            dirty.extend(new.map(|item| item.bounds));
+        }
+        self.items = items;
+        merge(dirty)
+    }
+
+    /// Forgets what was painted, so the next `update` repaints everything.
+    pub fn invalidate(&mut self) {
+        self.items.clear();
+    }
+
+    /// Repaints the shapes overlapping each of `regions`.
+    pub fn paint<P: Painter>(&self, painter: &mut P, regions: &[Region]) {
+        for region in regions {
+            painter.clip(region.x, region.y, region.width, region.height);
+            painter.clear_rect(region.x, region.y, region.width, region.height);
+            for item in self.items.iter().filter(|item| item.bounds.intersects(region)) {
+                draw(painter, item);
+            }
+            painter.unclip();
+        }
+    }
+
+    /// The tag of the topmost tagged shape under `(x, y)`.
+    pub fn hit(&self, x: f64, y: f64) -> Option<T> {
+        self.items
+            .iter()
+            .rev()
+            .filter(|item| item.tag.is_some())
+            .find(|item| contains(item, (x, y)))
+            .and_then(|item| item.tag.clone())
+    }
+}
+
+/// Draws a scene in one go, e.g. for export.
+impl<T: Clone + PartialEq> Drawing for Node<T> {
+    fn draw<P: Painter>(&self, painter: &mut P, width: f64, height: f64, _: Option<(f64, f64)>) {
+        painter.clear(width, height);
+        let mut retained = Retained::new();
+        retained.update(self);
+        let all = Region {
+            x: 0.0,
+            y: 0.0,
+            width,
+            height,
+        };
+        retained.paint(painter, &[all]);
+    }
+}
+
+fn flatten<T: Clone>(node: &Node<T>, transform: &Transform, out: &mut Vec<Item<T>>) {
+    match *node {
+        Node::Group {
+            transform: ref inner,
+            ref children,
+        } => {
+            let transform = transform.then(inner);
+            for child in children {
+                flatten(child, &transform, out);
+            }
+        }
+        Node::Shape {
+            ref shape,
+            ref style,
+            ref tag,
+        } => {
+            let mut style = style.clone();
+            style.line_width *= transform.stretch();
+            // Half the line sits outside the shape, plus a pixel for
+            // anti-aliasing.
+            let margin = style.line_width / 2.0 + 1.0;
+            let (figure, bounds) = match *shape {
+                Shape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                } => {
+                    let corners: Vec<_> = [(x, y), (x + width, y), (x + width, y + height), (x, y + height)]
+                        .iter()
+                        .map(|&point| transform.apply(point))
+                        .collect();
+                    let bounds = Region::around(&corners, margin);
+                    (Figure::Polygon(corners), bounds)
+                }
+                Shape::Circle { x, y, radius } => {
+                    let (x, y) = transform.apply((x, y));
+                    let radius = radius * transform.stretch();
+                    let bounds = Region::around(&[(x - radius, y - radius), (x + radius, y + radius)], margin);
+                    (Figure::Circle { x, y, radius }, bounds)
+                }
+                Shape::Path { ref points, closed } => {
+                    let points: Vec<_> = points.iter().map(|&point| transform.apply(point)).collect();
+                    let bounds = Region::around(&points, margin);
+                    if closed {
+                        (Figure::Polygon(points), bounds)
+                    } else {
+                        (Figure::Polyline(points), bounds)
+                    }
+                }
+                Shape::Text {
+                    ref text,
+                    x,
+                    y,
+                    align,
+                } => {
+                    let (x, y) = transform.apply((x, y));
+                    let width = CHAR_WIDTH * text.chars().count() as f64;
+                    let left = match align {
+                        Align::Start => x,
+                        Align::Center => x - width / 2.0,
+                        Align::End => x - width,
+                    };
+                    let half = LINE_HEIGHT / 2.0;
+                    let bounds = Region::around(&[(left, y - half), (left + width, y + half)], 1.0);
+                    let text = Figure::Text {
+                        text: text.clone(),
+                        x,
+                        y,
+                        align,
+                    };
+                    (text, bounds)
+                }
+            };
+            out.push(Item {
+                figure,
+                style,
+                tag: tag.clone(),
+                bounds,
+            });
+        }
+    }
+}
+
+fn draw<P: Painter, T>(painter: &mut P, item: &Item<T>) {
+    let style = &item.style;
+    match item.figure {
+        Figure::Polygon(ref points) => {
+            if let Some(ref fill) = style.fill {
+                painter.fill_polygon(points, fill);
+            }
+            if let Some(ref stroke) = style.stroke {
+                let mut outline = points.clone();
+                outline.extend(points.first().cloned());
+                painter.polyline(&outline, stroke, style.line_width);
+            }
+        }
+        Figure::Polyline(ref points) => {
+            if let Some(ref stroke) = style.stroke {
+                painter.polyline(points, stroke, style.line_width);
+            }
+        }
+        Figure::Circle { x, y, radius } => {
+            if let Some(ref fill) = style.fill {
+                painter.fill_circle(x, y, radius, fill);
+            }
+            if let Some(ref stroke) = style.stroke {
+                painter.stroke_circle(x, y, radius, stroke, style.line_width);
+            }
+        }
+        Figure::Text {
+            ref text,
+            x,
+            y,
+            align,
+        } => {
+            let color = style.fill.as_ref().map_or("#000", |fill| fill.as_str());
+            painter.text(text, x, y, align, color);
+        }
+    }
+}
+
+fn contains<T>(item: &Item<T>, point: (f64, f64)) -> bool {
+    let reach = item.style.line_width / 2.0;
+    match item.figure {
+        Figure::Polygon(ref points) => inside(points, point) || near_segments(points, true, point, reach),
+        Figure::Polyline(ref points) => near_segments(points, false, point, reach.max(2.0)),
+        Figure::Circle { x, y, radius } => {
+            let (dx, dy) = (point.0 - x, point.1 - y);
+            (dx * dx + dy * dy).sqrt() <= radius + reach
+        }
+        Figure::Text { .. } => {
+            let bounds = item.bounds;
+            point.0 >= bounds.x
+                && point.0 <= bounds.x + bounds.width
+                && point.1 >= bounds.y
+                && point.1 <= bounds.y + bounds.height
+        }
+    }
+}
+
+/// Even-odd point-in-polygon test.
+fn inside(points: &[(f64, f64)], (x, y): (f64, f64)) -> bool {
+    let mut inside = false;
+    let mut prev = match points.last() {
+        Some(&point) => point,
+        None => return false,
+    };
+    for &(px, py) in points {
+        let (qx, qy) = prev;
+        if (py > y) != (qy > y) && x < (qx - px) * (y - py) / (qy - py) + px {
+            inside = !inside;
+        }
+        prev = (px, py);
+    }
+    inside
+}
+
+fn near_segments(points: &[(f64, f64)], closed: bool, point: (f64, f64), reach: f64) -> bool {
+    let mut segments: Vec<_> = points.windows(2).map(|pair| (pair[0], pair[1])).collect();
+    if closed && points.len() > 2 {
+        segments.push((points[points.len() - 1], points[0]));
+    }
+    segments.iter().any(|&(start, end)| distance(start, end, point) <= reach)
+}
+
+/// Distance from `point` to the segment from `start` to `end`.
+fn distance(start: (f64, f64), end: (f64, f64), point: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length).clamp(0.0, 1.0)
+    };
+    let (x, y) = (start.0 + t * dx, start.1 + t * dy);
+    ((point.0 - x).powi(2) + (point.1 - y).powi(2)).sqrt()
+}
+
+/// Joins overlapping regions, and gives up on keeping them apart once
+/// there are too many.
+fn merge(mut regions: Vec<Region>) -> Vec<Region> {
+    let mut merged: Vec<Region> = Vec::new();
+    while let Some(mut region) = regions.pop() {
+        // Growing a region can make it reach ones already merged.
+        while let Some(idx) = merged.iter().position(|other| other.intersects(&region)) {
+            region = region.union(&merged.swap_remove(idx));
+        }
+        merged.push(region);
+    }
+    if merged.len() > MAX_REGIONS {
+        let first = merged[0];
+        return vec![merged.iter().fold(first, |all, region| all.union(region))];
+    }
+    merged
+}
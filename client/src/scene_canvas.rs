@@ -0,0 +1,121 @@
+//! A `<canvas>` showing a retained scene. Only the parts of the scene that
+//! changed are repainted, and clicks on tagged shapes are reported through
+//! `onhit`.
+
+use stdweb::traits::*;
+use stdweb::web::event::ClickEvent;
+use stdweb::web::html_element::CanvasElement;
+use stdweb::web::{CanvasRenderingContext2d, EventListenerHandle};
+use yew::prelude::*;
+use yew::virtual_dom::VNode;
+
+use canvas::{self, ContextPainter};
+use scene::{Node, Retained};
+
+pub struct SceneCanvas<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    element: CanvasElement,
+    context: CanvasRenderingContext2d,
+    props: Props<T>,
+    retained: Retained<T>,
+    listener: Option<EventListenerHandle>,
+}
+
+pub enum Msg {
+    Click(f64, f64),
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Props<T> {
+    /// Size in CSS pixels.
+    pub width: u32,
+    pub height: u32,
+    pub scene: Node<T>,
+    /// Called with the tag of the shape that was clicked.
+    pub onhit: Option<Callback<T>>,
+}
+
+impl<T> Default for Props<T> {
+    fn default() -> Self {
+        Props {
+            width: 0,
+            height: 0,
+            scene: Node::default(),
+            onhit: None,
+        }
+    }
+}
+
+impl<T> Component for SceneCanvas<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    type Message = Msg;
+    type Properties = Props<T>;
+
+    fn create(props: Self::Properties, mut link: ComponentLink<Self>) -> Self {
+        let (element, context) = canvas::new_canvas();
+        let clicked = link.send_back(|event: ClickEvent| Msg::Click(event.offset_x(), event.offset_y()));
+        let listener = element.add_event_listener(move |event: ClickEvent| clicked.emit(event));
+        let mut view = SceneCanvas {
+            element,
+            context,
+            props,
+            retained: Retained::new(),
+            listener: Some(listener),
+        };
+        canvas::resize(&view.element, view.props.width, view.props.height);
+        view.paint();
+        view
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Click(x, y) => {
+                if let (Some(tag), Some(ref onhit)) = (self.retained.hit(x, y), self.props.onhit.as_ref()) {
+                    onhit.emit(tag);
+                }
+            }
+        }
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props == self.props {
+            return false;
+        }
+        self.props = props;
+        if canvas::resize(&self.element, self.props.width, self.props.height) {
+            self.retained.invalidate();
+        }
+        self.paint();
+        false
+    }
+
+    fn destroy(&mut self) {
+        if let Some(listener) = self.listener.take() {
+            listener.remove();
+        }
+    }
+}
+
+impl<T> SceneCanvas<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    fn paint(&mut self) {
+        let dirty = self.retained.update(&self.props.scene);
+        self.retained.paint(&mut ContextPainter(&self.context), &dirty);
+    }
+}
+
+impl<T> Renderable<SceneCanvas<T>> for SceneCanvas<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    fn view(&self) -> Html<Self> {
+        VNode::VRef(self.element.as_node().clone())
+    }
+}
@@ -0,0 +1,116 @@
+//! Per-user preferences: step sizes, the decrement prompt, logging and the
+//! theme. Kept apart from any one counter so they follow the user around
+//! the app, and exported as a plain JSON file.
+
+use std::fmt;
+
+use serde_json;
+
+use counter::Config;
+use logging::Level;
+use theme;
+
+/// Largest step the settings accept. Bigger ones are almost certainly
+/// typos.
+pub const MAX_STEP: i64 = 1_000_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Amount added by `Command::Increment`.
+    pub increment: i64,
+    /// Amount subtracted by `Command::Decrement`.
+    pub decrement: i64,
+    /// Whether a `Decrement` asks for confirmation first.
+    pub confirm_decrement: bool,
+    /// The least severe log level that reaches the console.
+    pub log_level: Level,
+    /// One of `theme::names()`.
+    pub theme: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings::for_config(&Config::default())
+    }
+}
+
+/// A setting that can't be used as given.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invalid {
+    pub field: &'static str,
+    pub reason: String,
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.reason)
+    }
+}
+
+/// Whether `step` will do as the step size in `field`. Shared links are
+/// held to the same limits as the settings page.
+pub fn check_step(field: &'static str, step: i64) -> Result<(), Invalid> {
+    if (1..=MAX_STEP).contains(&step) {
+        Ok(())
+    } else {
+        Err(Invalid {
+            field,
+            reason: format!("must be between 1 and {}", MAX_STEP),
+        })
+    }
+}
+
+impl Settings {
+    /// Everything wrong with these settings; empty if they can be used.
+    pub fn validate(&self) -> Vec<Invalid> {
+        let mut problems = Vec::new();
+        for &(field, step) in &[("increment", self.increment), ("decrement", self.decrement)] {
+            if let Err(problem) = check_step(field, step) {
+                problems.push(problem);
+            }
+        }
+        if !theme::names().contains(&self.theme.as_str()) {
+            problems.push(Invalid {
+                field: "theme",
+                reason: format!("`{}` isn't a theme", self.theme),
+            });
+        }
+        problems
+    }
+
+    /// The defaults for counters that otherwise run with `config`: its step
+    /// sizes, and the usual everything else.
+    pub fn for_config(config: &Config) -> Settings {
+        Settings {
+            increment: config.increment,
+            decrement: config.decrement,
+            confirm_decrement: true,
+            log_level: Level::Info,
+            theme: theme::SYSTEM.into(),
+        }
+    }
+
+    /// `config` with these settings' step sizes.
+    pub fn configure(&self, config: &Config) -> Config {
+        Config {
+            increment: self.increment,
+            decrement: self.decrement,
+            ..config.clone()
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("settings always serialize")
+    }
+
+    /// Reads settings written by `to_json`. Missing fields take their
+    /// defaults; unknown ones, or values that don't validate, are refused.
+    pub fn from_json(text: &str) -> Result<Settings, String> {
+        let settings: Settings = serde_json::from_str(text).map_err(|err| err.to_string())?;
+        match settings.validate().into_iter().next() {
+            Some(problem) => Err(problem.to_string()),
+            None => Ok(settings),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! The settings page: edits the user's `Settings`, applying each change as
+//! soon as it validates.
+
+use yew::prelude::*;
+use yew::services::reader::{File, FileData, ReaderService, ReaderTask};
+
+use download;
+use logging::Level;
+use settings::{Invalid, Settings};
+use storage::Upgrade;
+use theme;
+
+impl Upgrade for Settings {}
+
+pub struct SettingsPage {
+    link: ComponentLink<SettingsPage>,
+    props: Props,
+    /// The form's contents, which may not validate yet.
+    draft: Settings,
+    /// The step fields as typed, so half-typed numbers survive a render.
+    increment: String,
+    decrement: String,
+    /// What's wrong with `draft`, by field.
+    problems: Vec<Invalid>,
+    reader: ReaderService,
+    /// A settings file being read for import.
+    importing: Option<ReaderTask>,
+    /// Why the last import failed.
+    import_error: Option<String>,
+}
+
+pub enum Msg {
+    Increment(String),
+    Decrement(String),
+    ToggleConfirmDecrement,
+    LogLevel(String),
+    Theme(String),
+    /// Puts every setting back to its default.
+    Reset,
+    /// Downloads the settings as JSON.
+    Export,
+    /// Files picked for import; only the first is read.
+    Import(Vec<File>),
+    /// A picked settings file finished reading.
+    Imported(FileData),
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct Props {
+    pub settings: Settings,
+    /// What "Restore defaults" goes back to.
+    pub defaults: Settings,
+    /// Called with the new settings after every valid change.
+    pub onchange: Option<Callback<Settings>>,
+}
+
+impl Component for SettingsPage {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut page = SettingsPage {
+            link,
+            draft: props.settings.clone(),
+            props,
+            increment: String::new(),
+            decrement: String::new(),
+            problems: Vec::new(),
+            reader: ReaderService::new(),
+            importing: None,
+            import_error: None,
+        };
+        page.reset_form();
+        page
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Increment(text) => self.increment = text,
+            Msg::Decrement(text) => self.decrement = text,
+            Msg::ToggleConfirmDecrement => self.draft.confirm_decrement = !self.draft.confirm_decrement,
+            Msg::LogLevel(name) => match name.parse() {
+                Ok(level) => self.draft.log_level = level,
+                Err(_) => return false,
+            },
+            Msg::Theme(name) => self.draft.theme = name,
+            Msg::Reset => {
+                let defaults = self.props.defaults.clone();
+                self.emit(defaults);
+                return false;
+            }
+            Msg::Export => {
+                download::save("settings.json", "application/json", &self.props.settings.to_json());
+                return false;
+            }
+            Msg::Import(files) => {
+                if let Some(file) = files.into_iter().next() {
+                    let callback = self.link.send_back(Msg::Imported);
+                    self.importing = Some(self.reader.read_file(file, callback));
+                }
+                return false;
+            }
+            Msg::Imported(data) => {
+                self.importing = None;
+                let result = String::from_utf8(data.content)
+                    .map_err(|_| "not a text file".to_string())
+                    .and_then(|text| Settings::from_json(&text));
+                match result {
+                    Ok(settings) => {
+                        self.import_error = None;
+                        self.emit(settings);
+                    }
+                    Err(err) => self.import_error = Some(err),
+                }
+                return true;
+            }
+        }
+        self.check();
+        if self.problems.is_empty() && self.draft != self.props.settings {
+            let settings = self.draft.clone();
+            self.emit(settings);
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let changed = props.settings != self.props.settings;
+        self.props = props;
+        if changed && self.props.settings != self.draft {
+            // Changed from outside the form, e.g. by an import.
+            self.reset_form();
+        }
+        true
+    }
+}
+
+impl SettingsPage {
+    fn emit(&self, settings: Settings) {
+        if let Some(ref onchange) = self.props.onchange {
+            onchange.emit(settings);
+        }
+    }
+
+    /// Fills the form from the current settings.
+    fn reset_form(&mut self) {
+        self.draft = self.props.settings.clone();
+        self.increment = self.draft.increment.to_string();
+        self.decrement = self.draft.decrement.to_string();
+        self.problems = Vec::new();
+    }
+
+    /// Reads the step fields into `draft` and collects what's wrong.
+    fn check(&mut self) {
+        self.problems = Vec::new();
+        match self.increment.trim().parse() {
+            Ok(step) => self.draft.increment = step,
+            Err(_) => self.problems.push(not_a_number("increment")),
+        }
+        match self.decrement.trim().parse() {
+            Ok(step) => self.draft.decrement = step,
+            Err(_) => self.problems.push(not_a_number("decrement")),
+        }
+        for problem in self.draft.validate() {
+            if self.problem(problem.field).is_none() {
+                self.problems.push(problem);
+            }
+        }
+    }
+
+    fn problem(&self, field: &str) -> Option<&Invalid> {
+        self.problems.iter().find(|problem| problem.field == field)
+    }
+
+    fn view_problem(&self, field: &str) -> Html<SettingsPage> {
+        match self.problem(field) {
+            Some(problem) => html! { <span class="error",>{ &problem.reason }</span> },
+            None => html! { <span></span> },
+        }
+    }
+}
+
+fn not_a_number(field: &'static str) -> Invalid {
+    Invalid {
+        field,
+        reason: "must be a whole number".into(),
+    }
+}
+
+impl Renderable<SettingsPage> for SettingsPage {
+    fn view(&self) -> Html<Self> {
+        let import_error = match self.import_error {
+            Some(ref err) => html! { <p class="error",>{ format!("Import failed: {}", err) }</p> },
+            None => html! { <div></div> },
+        };
+        html! {
+            <section class="settings",>
+                <h2>{ "Settings" }</h2>
+                <label>
+                    { "Increment by " }
+                    <input type="text", value=&self.increment, oninput=|e| Msg::Increment(e.value),/>
+                    { self.view_problem("increment") }
+                </label>
+                <label>
+                    { "Decrement by " }
+                    <input type="text", value=&self.decrement, oninput=|e| Msg::Decrement(e.value),/>
+                    { self.view_problem("decrement") }
+                </label>
+                <label>
+                    <input type="checkbox", checked=self.draft.confirm_decrement, onclick=|_| Msg::ToggleConfirmDecrement,/>
+                    { " Ask before decrementing" }
+                </label>
+                <label>
+                    { "Console logging " }
+                    <select onchange=|value| Msg::LogLevel(selected(value)),>
+                        { for Level::ALL.iter().map(|&level| html! {
+                            <option value=level.to_string(), selected=level == self.draft.log_level,>{ level }</option>
+                        }) }
+                    </select>
+                </label>
+                <label>
+                    { "Theme " }
+                    <select onchange=|value| Msg::Theme(selected(value)),>
+                        { for theme::names().into_iter().map(|theme| html! {
+                            <option value=theme, selected=theme == self.draft.theme,>{ theme }</option>
+                        }) }
+                    </select>
+                    { self.view_problem("theme") }
+                </label>
+                <nav class="menu",>
+                    <button onclick=|_| Msg::Reset,>{ "Restore defaults" }</button>
+                    <button onclick=|_| Msg::Export,>{ "Export settings" }</button>
+                    <label>
+                        { "Import settings " }
+                        <input type="file", accept=".json", onchange=|value| {
+                            let mut files = Vec::new();
+                            if let ChangeData::Files(list) = value {
+                                files.extend(list);
+                            }
+                            Msg::Import(files)
+                        },/>
+                    </label>
+                </nav>
+                { import_error }
+            </section>
+        }
+    }
+}
+
+/// The picked option of a `<select>`.
+fn selected(value: ChangeData) -> String {
+    match value {
+        ChangeData::Select(select) => select.value().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,162 @@
+//! Counter state and configuration as URL query parameters, for links like
+//! `#/counters/3?value=42&step=5`.
+//!
+//! Keys are `value`, `increment`, `decrement`, `step` (both of the last
+//! two), `min`, `max` and `overflow`. Anything else, a repeated key, or a
+//! value that doesn't fit the rest is refused outright rather than half
+//! applied.
+
+use std::fmt;
+
+use counter::{Config, Overflow, Total};
+use settings::{self, Invalid};
+
+/// Longest query `decode` will read. Real links are far shorter.
+const MAX_LEN: usize = 512;
+
+/// What a link overrides. `None` keeps the counter's own setting.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Params {
+    pub value: Option<Total>,
+    pub increment: Option<i64>,
+    pub decrement: Option<i64>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub overflow: Option<Overflow>,
+}
+
+/// Why a query was refused.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamError {
+    TooLong,
+    /// Not `key=value`, or characters no link of ours would contain.
+    Malformed(String),
+    Unknown(String),
+    Repeated(String),
+    Invalid { key: String, value: String },
+    /// A number, but not one the settings page would accept either.
+    Setting(Invalid),
+    /// Each setting is fine alone, but not together.
+    Inconsistent(&'static str),
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParamError::TooLong => write!(f, "the link is too long"),
+            ParamError::Malformed(ref pair) => write!(f, "`{}` isn't a setting", pair),
+            ParamError::Unknown(ref key) => write!(f, "unknown setting `{}`", key),
+            ParamError::Repeated(ref key) => write!(f, "`{}` is given twice", key),
+            ParamError::Invalid { ref key, ref value } => write!(f, "`{}` isn't a valid {}", value, key),
+            ParamError::Setting(ref invalid) => write!(f, "{}", invalid),
+            ParamError::Inconsistent(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl Params {
+    /// `base` with this link's overrides applied.
+    pub fn configure(&self, base: &Config) -> Config {
+        Config {
+            increment: self.increment.unwrap_or(base.increment),
+            decrement: self.decrement.unwrap_or(base.decrement),
+            min: self.min.or(base.min),
+            max: self.max.or(base.max),
+            overflow: self.overflow.unwrap_or(base.overflow),
+            ..base.clone()
+        }
+    }
+}
+
+/// Reads a query string, without the leading `?`, for a counter that would
+/// otherwise run with `base`.
+pub fn decode(query: &str, base: &Config) -> Result<Params, ParamError> {
+    if query.len() > MAX_LEN {
+        return Err(ParamError::TooLong);
+    }
+    let mut params = Params::default();
+    let mut seen: Vec<&str> = Vec::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let mut parts = pair.splitn(2, '=');
+        let (key, value) = match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) if !value.is_empty() && pair.chars().all(allowed) => (key, value),
+            _ => return Err(ParamError::Malformed(pair.to_string())),
+        };
+        let keys: &[&str] = if key == "step" { &["increment", "decrement"] } else { &[key][..] };
+        for &key in keys {
+            if seen.contains(&key) {
+                return Err(ParamError::Repeated(key.to_string()));
+            }
+            seen.push(key);
+        }
+        let invalid = || ParamError::Invalid {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        match key {
+            "value" => params.value = Some(value.parse().map_err(|_| invalid())?),
+            "increment" | "decrement" | "step" => {
+                let amount: i64 = value.parse().map_err(|_| invalid())?;
+                let field = match key {
+                    "increment" => "increment",
+                    "decrement" => "decrement",
+                    _ => "step",
+                };
+                settings::check_step(field, amount).map_err(ParamError::Setting)?;
+                if key != "decrement" {
+                    params.increment = Some(amount);
+                }
+                if key != "increment" {
+                    params.decrement = Some(amount);
+                }
+            }
+            "min" => params.min = Some(value.parse().map_err(|_| invalid())?),
+            "max" => params.max = Some(value.parse().map_err(|_| invalid())?),
+            "overflow" => params.overflow = Some(value.parse().map_err(|_| invalid())?),
+            _ => return Err(ParamError::Unknown(key.to_string())),
+        }
+    }
+    let config = params.configure(base);
+    if let (Some(min), Some(max)) = (config.min, config.max) {
+        if min > max {
+            return Err(ParamError::Inconsistent("the minimum is above the maximum"));
+        }
+    }
+    if let Some(ref value) = params.value {
+        if config.overflow != Overflow::Unbounded && value.to_i64().is_none() {
+            return Err(ParamError::Inconsistent("the value is too large for this counter"));
+        }
+        if &config.clamp_total(value) != value {
+            return Err(ParamError::Inconsistent("the value is outside the counter's bounds"));
+        }
+    }
+    Ok(params)
+}
+
+/// Writes a query that `decode` turns back into `value` and `config`,
+/// leaving out whatever already matches `base`.
+pub fn encode(value: &Total, config: &Config, base: &Config) -> String {
+    let mut pairs = vec![format!("value={}", value)];
+    if config.increment != base.increment || config.decrement != base.decrement {
+        if config.increment == config.decrement {
+            pairs.push(format!("step={}", config.increment));
+        } else {
+            pairs.push(format!("increment={}", config.increment));
+            pairs.push(format!("decrement={}", config.decrement));
+        }
+    }
+    if let Some(min) = config.min.filter(|_| config.min != base.min) {
+        pairs.push(format!("min={}", min));
+    }
+    if let Some(max) = config.max.filter(|_| config.max != base.max) {
+        pairs.push(format!("max={}", max));
+    }
+    if config.overflow != base.overflow {
+        pairs.push(format!("overflow={}", config.overflow.name()));
+    }
+    pairs.join("&")
+}
+
+fn allowed(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '='
+}
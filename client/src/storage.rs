@@ -0,0 +1,94 @@
+//! Versioned persistence of component state in the browser's localStorage.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use yew::format::Json;
+use yew::services::storage::{Area, StorageService};
+
+/// localStorage key a standalone counter is saved under.
+pub const COUNTER_KEY: &str = "rust-web-experiments.counter";
+/// localStorage key the list of named counters is saved under.
+pub const LIST_KEY: &str = "rust-web-experiments.counters";
+/// localStorage key the log behind the history page is saved under.
+pub const ACTIVITY_KEY: &str = "rust-web-experiments.activity";
+/// localStorage key the user's settings are saved under.
+pub const SETTINGS_KEY: &str = "rust-web-experiments.settings";
+/// localStorage key the replica ids handed out to tabs are saved under.
+pub const REPLICAS_KEY: &str = "rust-web-experiments.replicas";
+
+/// localStorage key the journal of the counter syncing on `channel` is
+/// saved under.
+pub fn journal_key(channel: &str) -> String {
+    format!("rust-web-experiments.journal.{}", channel)
+}
+
+/// Schema version written by this build. Bump it whenever a stored type
+/// changes shape, and teach that type's `Upgrade` impl to read the previous
+/// layout.
+pub const VERSION: u32 = 2;
+
+/// The on-disk wrapper. `state` is kept as raw JSON until we know which
+/// version we're looking at.
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u32,
+    state: T,
+}
+
+pub struct Store {
+    storage: StorageService,
+    key: String,
+}
+
+impl Store {
+    pub fn new(key: &str) -> Self {
+        Store {
+            storage: StorageService::new(Area::Local),
+            key: key.to_string(),
+        }
+    }
+
+    /// Restores the saved state. Missing, corrupted, or unknown-version
+    /// entries come back as `None` instead of failing.
+    pub fn load<T: Upgrade>(&mut self) -> Option<T> {
+        if let Json(Ok(envelope)) = self.storage.restore(&self.key) {
+            migrate(envelope)
+        } else {
+            None
+        }
+    }
+
+    pub fn save<T: Serialize>(&mut self, state: &T) {
+        let envelope = Envelope {
+            version: VERSION,
+            state,
+        };
+        self.storage.store(&self.key, Json(&envelope));
+    }
+
+    /// Forgets the saved state.
+    pub fn clear(&mut self) {
+        self.storage.remove(&self.key);
+    }
+}
+
+/// A stored type that may have been saved in an older layout.
+pub trait Upgrade: DeserializeOwned {
+    /// Reads `state` as written by schema `version`, which is always older
+    /// than `VERSION`. Types that never changed shape keep the default and
+    /// drop old entries.
+    fn upgrade(_version: u32, _state: Value) -> Option<Self> {
+        None
+    }
+}
+
+/// Decodes a stored envelope into the current layout, upgrading older
+/// versions as needed. Returns `None` for anything we can't make sense of.
+fn migrate<T: Upgrade>(envelope: Envelope<Value>) -> Option<T> {
+    match envelope.version {
+        VERSION => ::serde_json::from_value(envelope.state).ok(),
+        version if version < VERSION => T::upgrade(version, envelope.state),
+        _ => None,
+    }
+}
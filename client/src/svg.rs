@@ -0,0 +1,150 @@
+//! Renders drawings to SVG through the same `Painter` calls the canvas
+//! gets, so exported files match what's on screen.
+
+use std::fmt::Write;
+
+use paint::{Align, Drawing, Painter};
+
+/// Draws `drawing` on a `width` by `height` surface and returns the SVG
+/// document.
+pub fn render<D: Drawing>(drawing: &D, width: f64, height: f64) -> String {
+    let mut painter = SvgPainter::new(width, height);
+    drawing.draw(&mut painter, width, height, None);
+    painter.finish()
+}
+
+/// Collects drawing calls as SVG elements.
+pub struct SvgPainter {
+    out: String,
+    /// How many clip paths have been defined, for unique ids.
+    clips: usize,
+}
+
+impl SvgPainter {
+    pub fn new(width: f64, height: f64) -> Self {
+        let mut out = String::new();
+        write!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = width,
+            h = height
+        )
+        .unwrap();
+        out.push('\n');
+        SvgPainter { out, clips: 0 }
+    }
+
+    /// Closes the document and returns it.
+    pub fn finish(mut self) -> String {
+        self.out.push_str("</svg>\n");
+        self.out
+    }
+
+    fn line(&mut self, element: String) {
+        self.out.push_str(&element);
+        self.out.push('\n');
+    }
+}
+
+impl Painter for SvgPainter {
+    /// SVG has nothing to wipe; every export starts from a blank document.
+    fn clear_rect(&mut self, _: f64, _: f64, _: f64, _: f64) {}
+
+    fn polyline(&mut self, points: &[(f64, f64)], color: &str, width: f64) {
+        self.line(format!(
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            points_attr(points),
+            escape(color),
+            width
+        ));
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: &str) {
+        self.line(format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            x,
+            y,
+            width,
+            height,
+            escape(color)
+        ));
+    }
+
+    fn fill_polygon(&mut self, points: &[(f64, f64)], color: &str) {
+        self.line(format!(
+            r#"<polygon points="{}" fill="{}"/>"#,
+            points_attr(points),
+            escape(color)
+        ));
+    }
+
+    fn fill_circle(&mut self, x: f64, y: f64, radius: f64, color: &str) {
+        self.line(format!(
+            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+            x,
+            y,
+            radius,
+            escape(color)
+        ));
+    }
+
+    fn stroke_circle(&mut self, x: f64, y: f64, radius: f64, color: &str, width: f64) {
+        self.line(format!(
+            r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            x,
+            y,
+            radius,
+            escape(color),
+            width
+        ));
+    }
+
+    fn text(&mut self, text: &str, x: f64, y: f64, align: Align, color: &str) {
+        let anchor = match align {
+            Align::Start => "start",
+            Align::Center => "middle",
+            Align::End => "end",
+        };
+        self.line(format!(
+            r#"<text x="{}" y="{}" text-anchor="{}" dominant-baseline="middle" font-family="sans-serif" font-size="12" fill="{}">{}</text>"#,
+            x,
+            y,
+            anchor,
+            escape(color),
+            escape(text)
+        ));
+    }
+
+    fn clip(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.clips += 1;
+        self.line(format!(
+            r#"<clipPath id="clip{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+            self.clips, x, y, width, height
+        ));
+        self.line(format!(r#"<g clip-path="url(#clip{})">"#, self.clips));
+    }
+
+    fn unclip(&mut self) {
+        self.line("</g>".to_string());
+    }
+}
+
+fn points_attr(points: &[(f64, f64)]) -> String {
+    let points: Vec<String> = points.iter().map(|&(x, y)| format!("{},{}", x, y)).collect();
+    points.join(" ")
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,166 @@
+//! Keeps a counter in step with other tabs and users through the server's
+//! WebSocket endpoint. Changes made while the connection is down, even
+//! before a reload, are caught up by sending the whole local state every
+//! time it opens; merging makes that safe to repeat.
+
+use std::cmp;
+use std::time::Duration;
+
+use failure::Error;
+use yew::format::Json;
+use yew::prelude::*;
+use yew::services::websocket::{WebSocketService, WebSocketStatus, WebSocketTask};
+use yew::services::{Task, TimeoutService};
+
+use model::{Model, Msg};
+use shared::{ClientMessage, PnCounter, ServerMessage};
+
+/// Wait before the first reconnect attempt; doubled after every failure.
+const MIN_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 30_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Connecting,
+    Online,
+    Offline,
+}
+
+pub enum Event {
+    /// A message from the server, or `None` if it couldn't be decoded.
+    Received(Option<ServerMessage>),
+    Opened,
+    Lost,
+    /// Time to try connecting again.
+    Retry,
+}
+
+pub struct SyncClient {
+    url: String,
+    counter: String,
+    ws: WebSocketService,
+    timeout: TimeoutService,
+    task: Option<WebSocketTask>,
+    retry: Option<Box<Task>>,
+    status: Status,
+    backoff: Duration,
+    /// Local changes were made while offline and haven't been sent yet.
+    pending: bool,
+    on_message: Callback<Json<Result<ServerMessage, Error>>>,
+    on_status: Callback<WebSocketStatus>,
+    on_retry: Callback<()>,
+}
+
+impl SyncClient {
+    /// Starts connecting to `url` to follow the server-side counter named
+    /// `counter`.
+    pub fn new(url: String, counter: String, link: &mut ComponentLink<Model>) -> Self {
+        let mut sync = SyncClient {
+            url,
+            counter,
+            ws: WebSocketService::new(),
+            timeout: TimeoutService::new(),
+            task: None,
+            retry: None,
+            status: Status::Connecting,
+            backoff: Duration::from_millis(MIN_BACKOFF_MS),
+            pending: false,
+            on_message: link.send_back(|Json(data): Json<Result<ServerMessage, Error>>| {
+                Msg::Sync(Event::Received(data.ok()))
+            }),
+            on_status: link.send_back(|status| {
+                Msg::Sync(match status {
+                    WebSocketStatus::Opened => Event::Opened,
+                    WebSocketStatus::Closed | WebSocketStatus::Error => Event::Lost,
+                })
+            }),
+            on_retry: link.send_back(|_| Msg::Sync(Event::Retry)),
+        };
+        sync.connect();
+        sync
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Whether local changes are waiting for the connection to come back.
+    pub fn pending(&self) -> bool {
+        self.pending
+    }
+
+    pub fn counter(&self) -> &str {
+        &self.counter
+    }
+
+    /// Switches to following a different server-side counter.
+    pub fn follow(&mut self, counter: String) {
+        self.counter = counter;
+        if self.status == Status::Online {
+            let subscribe = ClientMessage::Subscribe(self.counter.clone());
+            self.send(subscribe);
+        }
+    }
+
+    /// Sends `message` now, or drops it while offline: the whole state
+    /// goes out once the connection is back, covering it.
+    pub fn send(&mut self, message: ClientMessage) {
+        match self.task {
+            Some(ref mut task) if self.status == Status::Online => task.send(Json(&message)),
+            _ => self.pending = true,
+        }
+    }
+
+    /// Reacts to a connection event, given the local copy of the counter.
+    /// Returns the server's copy if it sent one, ready to merge.
+    pub fn handle(&mut self, event: Event, local: &PnCounter) -> Option<PnCounter> {
+        match event {
+            Event::Received(Some(ServerMessage::Updated { counter, state })) => {
+                if counter == self.counter {
+                    return Some(state);
+                }
+            }
+            Event::Received(None) => {}
+            Event::Opened => {
+                self.status = Status::Online;
+                self.backoff = Duration::from_millis(MIN_BACKOFF_MS);
+                // Order doesn't matter for merges, but catching up first
+                // shows other tabs' changes sooner.
+                let subscribe = ClientMessage::Subscribe(self.counter.clone());
+                self.send(subscribe);
+                if !local.is_empty() {
+                    let merge = ClientMessage::Merge {
+                        counter: self.counter.clone(),
+                        delta: local.clone(),
+                    };
+                    self.send(merge);
+                }
+                self.pending = false;
+            }
+            Event::Lost => {
+                // Browsers report an error and then a close for the same
+                // failure; only schedule one retry.
+                if self.task.take().is_some() {
+                    self.status = Status::Offline;
+                    let handle = self.timeout.spawn(self.backoff, self.on_retry.clone());
+                    self.retry = Some(Box::new(handle));
+                    let max = Duration::from_millis(MAX_BACKOFF_MS);
+                    self.backoff = cmp::min(self.backoff * 2, max);
+                }
+            }
+            Event::Retry => {
+                self.retry = None;
+                self.connect();
+            }
+        }
+        None
+    }
+
+    fn connect(&mut self) {
+        self.status = Status::Connecting;
+        let task = self
+            .ws
+            .connect(&self.url, self.on_message.clone(), self.on_status.clone());
+        self.task = Some(task);
+    }
+}
@@ -0,0 +1,286 @@
+//! Named colour themes and the stylesheet built from them. Each theme
+//! becomes a set of CSS custom properties; the rules themselves only ever
+//! refer to those, so switching themes is a matter of which set applies.
+
+/// The theme name that follows the browser's `prefers-color-scheme`.
+pub const SYSTEM: &str = "system";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Page background.
+    pub background: &'static str,
+    /// Background of cards, dialogs and inputs.
+    pub surface: &'static str,
+    pub text: &'static str,
+    /// Secondary text, e.g. timestamps.
+    pub muted: &'static str,
+    pub border: &'static str,
+    /// Buttons and links.
+    pub accent: &'static str,
+    /// Text on top of `accent`.
+    pub on_accent: &'static str,
+    pub error: &'static str,
+    /// Behind the open dialog.
+    pub backdrop: &'static str,
+}
+
+pub const LIGHT: Theme = Theme {
+    name: "light",
+    background: "#fafafa",
+    surface: "#ffffff",
+    text: "#222222",
+    muted: "#6b6b6b",
+    border: "#d6d6d6",
+    accent: "#2962ff",
+    on_accent: "#ffffff",
+    error: "#c0392b",
+    backdrop: "rgba(0, 0, 0, 0.4)",
+};
+
+pub const DARK: Theme = Theme {
+    name: "dark",
+    background: "#121212",
+    surface: "#1e1e1e",
+    text: "#e8e8e8",
+    muted: "#9e9e9e",
+    border: "#3a3a3a",
+    accent: "#82b1ff",
+    on_accent: "#0d1b33",
+    error: "#ff6e6e",
+    backdrop: "rgba(0, 0, 0, 0.6)",
+};
+
+pub const HIGH_CONTRAST: Theme = Theme {
+    name: "high-contrast",
+    background: "#000000",
+    surface: "#000000",
+    text: "#ffffff",
+    muted: "#ffffff",
+    border: "#ffffff",
+    accent: "#ffff00",
+    on_accent: "#000000",
+    error: "#ff4040",
+    backdrop: "rgba(0, 0, 0, 0.8)",
+};
+
+/// Every theme, in the order they're offered.
+pub const THEMES: [&Theme; 3] = [&LIGHT, &DARK, &HIGH_CONTRAST];
+
+impl Default for Theme {
+    fn default() -> Self {
+        LIGHT
+    }
+}
+
+/// Looks a theme up by name. `SYSTEM` isn't a theme of its own.
+pub fn find(name: &str) -> Option<&'static Theme> {
+    THEMES.iter().cloned().find(|theme| theme.name == name)
+}
+
+/// The theme the stylesheet ends up using for `name`, given whether the
+/// browser prefers a dark colour scheme. For drawings, which can't use the
+/// stylesheet's custom properties.
+pub fn resolve(name: &str, prefers_dark: bool) -> &'static Theme {
+    match find(name) {
+        Some(theme) => theme,
+        None if prefers_dark => &DARK,
+        None => &LIGHT,
+    }
+}
+
+/// Every name a user can pick: `SYSTEM`, then each theme.
+pub fn names() -> Vec<&'static str> {
+    let mut names = vec![SYSTEM];
+    names.extend(THEMES.iter().map(|theme| theme.name));
+    names
+}
+
+impl Theme {
+    /// The theme as CSS custom property declarations, one per line.
+    pub fn custom_properties(&self) -> String {
+        let properties = [
+            ("background", self.background),
+            ("surface", self.surface),
+            ("text", self.text),
+            ("muted", self.muted),
+            ("border", self.border),
+            ("accent", self.accent),
+            ("on-accent", self.on_accent),
+            ("error", self.error),
+            ("backdrop", self.backdrop),
+        ];
+        let mut out = String::new();
+        for &(property, value) in &properties {
+            out.push_str(&format!("  --{}: {};\n", property, value));
+        }
+        out
+    }
+}
+
+/// The whole stylesheet. Without a `data-theme` attribute on the root
+/// element, or with it set to `SYSTEM`, the light or dark theme is picked
+/// to match `prefers-color-scheme`; naming a theme there overrides that.
+pub fn stylesheet() -> String {
+    let mut css = format!(":root {{\n{}}}\n", LIGHT.custom_properties());
+    css.push_str(&format!(
+        "@media (prefers-color-scheme: dark) {{\n:root {{\n{}}}\n}}\n",
+        DARK.custom_properties()
+    ));
+    for theme in THEMES.iter() {
+        css.push_str(&format!(
+            ":root[data-theme=\"{}\"] {{\n{}}}\n",
+            theme.name,
+            theme.custom_properties()
+        ));
+    }
+    css.push_str(RULES);
+    css
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use self::browser::{apply, current, install, SchemeListener};
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use stdweb::Value;
+    use yew::callback::Callback;
+
+    use super::{resolve, stylesheet, Theme, SYSTEM};
+
+    /// The media query behind `SYSTEM`.
+    const DARK_SCHEME: &str = "(prefers-color-scheme: dark)";
+
+    /// Adds the stylesheet to the page. Call once, at startup.
+    pub fn install() {
+        let css = stylesheet();
+        js! { @(no_return)
+            var style = document.createElement("style");
+            style.id = "theme";
+            style.textContent = @{css};
+            document.head.appendChild(style);
+        }
+    }
+
+    /// Switches to the named theme, or back to following the browser for
+    /// `SYSTEM`.
+    pub fn apply(name: &str) {
+        if name == SYSTEM {
+            js! { @(no_return) document.documentElement.removeAttribute("data-theme"); }
+        } else {
+            js! { @(no_return) document.documentElement.setAttribute("data-theme", @{name}); }
+        }
+    }
+
+    /// The theme in effect for `name`, following the browser for `SYSTEM`.
+    pub fn current(name: &str) -> &'static Theme {
+        let dark = js! {
+            return window.matchMedia(@{DARK_SCHEME}).matches;
+        };
+        resolve(name, dark == Value::Bool(true))
+    }
+
+    /// Reports the browser switching between light and dark, until dropped.
+    pub struct SchemeListener {
+        /// The media query and the handler listening to it.
+        listener: Value,
+    }
+
+    impl SchemeListener {
+        pub fn new(callback: Callback<()>) -> Self {
+            let notify = move || callback.emit(());
+            let listener = js! {
+                var query = window.matchMedia(@{DARK_SCHEME});
+                var notify = @{notify};
+                var handler = function() { notify(); };
+                query.addListener(handler);
+                return { query: query, notify: notify, handler: handler };
+            };
+            SchemeListener { listener }
+        }
+    }
+
+    impl Drop for SchemeListener {
+        fn drop(&mut self) {
+            js! { @(no_return)
+                var listener = @{&self.listener};
+                listener.query.removeListener(listener.handler);
+                listener.notify.drop();
+            }
+        }
+    }
+}
+
+/// Everything that isn't a colour.
+const RULES: &str = r#"
+body {
+  margin: 0 auto;
+  max-width: 40rem;
+  padding: 1rem;
+  font-family: system-ui, sans-serif;
+  background: var(--background);
+  color: var(--text);
+}
+a { color: var(--accent); }
+input, select, button {
+  font: inherit;
+  color: var(--text);
+  background: var(--surface);
+  border: 1px solid var(--border);
+  border-radius: 4px;
+  padding: 0.25rem 0.5rem;
+}
+button { cursor: pointer; }
+button:hover:not(:disabled) { border-color: var(--accent); }
+button:disabled { opacity: 0.5; cursor: default; }
+nav.menu {
+  display: flex;
+  flex-wrap: wrap;
+  gap: 0.5rem;
+  align-items: center;
+  margin: 0.5rem 0;
+}
+nav.pages { border-bottom: 1px solid var(--border); padding-bottom: 0.5rem; }
+nav.pages a { text-decoration: none; padding: 0.25rem 0.5rem; border-radius: 4px; }
+nav.pages a.active { background: var(--accent); color: var(--on-accent); }
+ul.counters { list-style: none; padding: 0; }
+li.counter {
+  background: var(--surface);
+  border: 1px solid var(--border);
+  border-radius: 6px;
+  padding: 0.75rem;
+  margin-bottom: 0.75rem;
+}
+li.counter.selected { border-color: var(--accent); }
+.error { color: var(--error); }
+.timestamp, .status { color: var(--muted); }
+.settings label { display: block; margin: 0.5rem 0; }
+table.activity { border-collapse: collapse; width: 100%; }
+table.activity td, table.activity th {
+  text-align: left;
+  padding: 0.25rem 0.5rem;
+  border-bottom: 1px solid var(--border);
+}
+.modal-backdrop {
+  position: fixed;
+  top: 0; right: 0; bottom: 0; left: 0;
+  display: flex;
+  align-items: center;
+  justify-content: center;
+  background: var(--backdrop);
+}
+.modal {
+  background: var(--surface);
+  border: 1px solid var(--border);
+  border-radius: 6px;
+  padding: 1rem 1.5rem;
+  min-width: 18rem;
+}
+.modal-actions { display: flex; justify-content: flex-end; gap: 0.5rem; }
+kbd {
+  border: 1px solid var(--border);
+  border-radius: 3px;
+  padding: 0 0.25rem;
+  font-family: monospace;
+}
+"#;
@@ -0,0 +1,89 @@
+//! A recording of a component's state after every message it handled, for
+//! stepping back through what happened. Works with any cloneable state.
+
+/// One handled message and the state it left behind.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Moment<T> {
+    pub label: String,
+    /// How deeply the message was nested, e.g. 1 for a `Bulk` child.
+    pub depth: usize,
+    pub state: T,
+}
+
+/// Something the user asked the timeline to do.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    /// Shows the state after the moment at this index.
+    Preview(usize),
+    Back,
+    Forward,
+    /// Goes back to showing the live state.
+    Resume,
+}
+
+pub struct Timeline<T> {
+    moments: Vec<Moment<T>>,
+    /// The moment being previewed, or `None` when following live.
+    cursor: Option<usize>,
+    limit: usize,
+}
+
+impl<T: Clone> Timeline<T> {
+    /// Creates an empty timeline that keeps the latest `limit` moments.
+    pub fn new(limit: usize) -> Self {
+        Timeline {
+            moments: Vec::new(),
+            cursor: None,
+            limit,
+        }
+    }
+
+    pub fn moments(&self) -> &[Moment<T>] {
+        &self.moments
+    }
+
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.cursor.is_none()
+    }
+
+    /// Appends a moment. A preview stays on the moment it was showing.
+    pub fn record<L: Into<String>>(&mut self, label: L, depth: usize, state: T) {
+        self.moments.push(Moment {
+            label: label.into(),
+            depth,
+            state,
+        });
+        if self.moments.len() > self.limit {
+            self.moments.remove(0);
+            self.cursor = self.cursor.map(|idx| idx.saturating_sub(1));
+        }
+    }
+
+    /// The state to show instead of the live one, if previewing.
+    pub fn shown(&self) -> Option<&T> {
+        self.cursor.map(|idx| &self.moments[idx].state)
+    }
+
+    pub fn handle(&mut self, action: Action) {
+        let last = match self.moments.len() {
+            0 => return,
+            len => len - 1,
+        };
+        self.cursor = match action {
+            Action::Preview(idx) => Some(idx.min(last)),
+            // The live state is the last moment's, so stepping back from
+            // live lands on the one before it.
+            Action::Back => Some(self.cursor.unwrap_or(last).saturating_sub(1)),
+            // Likewise, stepping onto the last moment is going live.
+            Action::Forward => match self.cursor {
+                Some(idx) if idx + 1 < last => Some(idx + 1),
+                _ => None,
+            },
+            Action::Resume => None,
+        };
+    }
+}
@@ -0,0 +1,126 @@
+//! A live clock that re-renders itself on an interval.
+
+use std::time::Duration;
+
+use stdweb::web::Date;
+use yew::prelude::*;
+use yew::services::{IntervalService, Task};
+
+use effects::{BrowserClock, Clock};
+use format::{self, TimeFormat};
+
+pub struct Timestamp {
+    clock: BrowserClock,
+    interval: IntervalService,
+    tick: Callback<()>,
+    job: Option<Box<Task>>,
+    props: Props,
+    now: f64,
+}
+
+pub enum Msg {
+    Tick,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Props {
+    /// How often the displayed time is refreshed. Zero stops the clock.
+    pub refresh: Duration,
+    pub format: TimeFormat,
+    /// The instant to show, in milliseconds since the Unix epoch. `None`
+    /// shows the current time.
+    pub at: Option<f64>,
+}
+
+impl Default for Props {
+    fn default() -> Self {
+        Props {
+            refresh: Duration::from_secs(1),
+            format: TimeFormat::LocaleShort,
+            at: None,
+        }
+    }
+}
+
+impl Component for Timestamp {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, mut link: ComponentLink<Self>) -> Self {
+        let clock = BrowserClock;
+        let mut timestamp = Timestamp {
+            now: clock.now(),
+            clock,
+            interval: IntervalService::new(),
+            tick: link.send_back(|_| Msg::Tick),
+            job: None,
+            props,
+        };
+        timestamp.restart();
+        timestamp
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Tick => self.now = self.clock.now(),
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let restart = props.refresh != self.props.refresh;
+        self.props = props;
+        if restart {
+            self.restart();
+        }
+        true
+    }
+
+    fn destroy(&mut self) {
+        self.stop();
+    }
+}
+
+impl Timestamp {
+    fn restart(&mut self) {
+        self.stop();
+        if self.props.refresh != Duration::from_secs(0) {
+            let handle = self.interval.spawn(self.props.refresh, self.tick.clone());
+            self.job = Some(Box::new(handle));
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut task) = self.job.take() {
+            task.cancel();
+        }
+    }
+}
+
+impl Renderable<Timestamp> for Timestamp {
+    fn view(&self) -> Html<Self> {
+        let at = self.props.at.unwrap_or(self.now);
+        let text = match self.props.format {
+            TimeFormat::LocaleShort => locale_short(at),
+            ref format => format::format(format, at, self.now),
+        };
+        html! {
+            <p class="timestamp",>{ text }</p>
+        }
+    }
+}
+
+/// Formats `at` with the browser's own locale rules.
+fn locale_short(at: f64) -> String {
+    let date = Date::from_time(at);
+    let text = js! {
+        return @{date}.toLocaleString(undefined, {
+            year: "numeric",
+            month: "short",
+            day: "numeric",
+            hour: "2-digit",
+            minute: "2-digit"
+        });
+    };
+    text.into_string().unwrap_or_else(|| format::format(&TimeFormat::LocaleShort, at, at))
+}
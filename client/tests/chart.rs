@@ -0,0 +1,116 @@
+extern crate client;
+
+use client::chart::{ticks, LineChart, Point};
+use client::paint::{Align, Drawing, Painter};
+
+/// Keeps the text it was asked to write and counts everything else.
+#[derive(Default)]
+struct Recorder {
+    texts: Vec<String>,
+    lines: usize,
+    circles: usize,
+}
+
+impl Painter for Recorder {
+    fn clear_rect(&mut self, _: f64, _: f64, _: f64, _: f64) {}
+
+    fn polyline(&mut self, _: &[(f64, f64)], _: &str, _: f64) {
+        self.lines += 1;
+    }
+
+    fn fill_rect(&mut self, _: f64, _: f64, _: f64, _: f64, _: &str) {}
+
+    fn fill_polygon(&mut self, _: &[(f64, f64)], _: &str) {}
+
+    fn fill_circle(&mut self, _: f64, _: f64, _: f64, _: &str) {
+        self.circles += 1;
+    }
+
+    fn stroke_circle(&mut self, _: f64, _: f64, _: f64, _: &str, _: f64) {
+        self.circles += 1;
+    }
+
+    fn text(&mut self, text: &str, _: f64, _: f64, _: Align, _: &str) {
+        self.texts.push(text.to_string());
+    }
+
+    fn clip(&mut self, _: f64, _: f64, _: f64, _: f64) {}
+
+    fn unclip(&mut self) {}
+}
+
+fn chart() -> LineChart {
+    LineChart {
+        points: vec![
+            Point { at: 0.0, value: 3 },
+            Point { at: 10_000.0, value: -7 },
+            Point { at: 20_000.0, value: 12 },
+        ],
+        ..LineChart::default()
+    }
+}
+
+#[test]
+fn ticks_are_round_and_cover_the_range() {
+    assert_eq!(ticks(0.0, 10.0, 5), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+    assert_eq!(ticks(-7.0, 12.0, 5), vec![-10.0, -5.0, 0.0, 5.0, 10.0, 15.0]);
+    let small = ticks(0.0, 0.4, 4);
+    assert_eq!(small.len(), 5);
+    assert!((small[4] - 0.4).abs() < 1e-9);
+}
+
+#[test]
+fn scale_fits_the_data() {
+    let chart = chart();
+    let scale = chart.scale(400.0, 200.0);
+    assert_eq!(scale.x, (0.0, 20_000.0));
+    assert_eq!(scale.y, (-10.0, 15.0));
+    let (left, top, right, bottom) = scale.area;
+    assert_eq!(scale.x(0.0), left);
+    assert_eq!(scale.x(20_000.0), right);
+    assert_eq!(scale.y(-10.0), bottom);
+    assert_eq!(scale.y(15.0), top);
+}
+
+#[test]
+fn flat_series_still_has_a_range() {
+    let chart = LineChart {
+        points: vec![Point { at: 5000.0, value: 4 }],
+        ..LineChart::default()
+    };
+    let scale = chart.scale(400.0, 200.0);
+    assert!(scale.x.0 < scale.x.1);
+    assert!(scale.y.0 < 4.0 && 4.0 < scale.y.1);
+}
+
+#[test]
+fn hover_shows_nearest_point() {
+    let chart = chart();
+    let scale = chart.scale(400.0, 200.0);
+    let x = scale.x(10_000.0) + 5.0;
+    assert_eq!(chart.nearest(&scale, x), Some(1));
+    assert_eq!(chart.nearest(&scale, scale.x(5000.0)), None);
+
+    let mut painter = Recorder::default();
+    chart.draw(&mut painter, 400.0, 200.0, Some((x, 50.0)));
+    assert_eq!(painter.circles, 1);
+    assert!(painter.texts.contains(&"-7 at 10.0s".to_string()));
+}
+
+#[test]
+fn draws_axes_without_tooltip() {
+    let chart = chart();
+    let mut painter = Recorder::default();
+    chart.draw(&mut painter, 400.0, 200.0, None);
+    assert_eq!(painter.circles, 0);
+    for label in &["-10", "0", "15", "0s", "20s"] {
+        assert!(painter.texts.contains(&label.to_string()), "missing {}", label);
+    }
+}
+
+#[test]
+fn empty_chart_says_so() {
+    let mut painter = Recorder::default();
+    LineChart::default().draw(&mut painter, 400.0, 200.0, None);
+    assert_eq!(painter.texts, vec!["No data yet".to_string()]);
+}
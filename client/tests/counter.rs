@@ -0,0 +1,197 @@
+extern crate client;
+
+use client::counter::{Command, Config, Counter, Overflow, PnCounter, State, Total};
+use client::logging::{Level, Logger, RingBuffer};
+
+fn counter(config: Config) -> Counter<RingBuffer> {
+    let state = State::from_value("a", config.initial);
+    let logger = Logger::new("counter", Level::Debug, RingBuffer::new(64));
+    Counter::new(config, "a".into(), state, logger)
+}
+
+#[test]
+fn increment_adds_step() {
+    let mut counter = counter(Config::default());
+    assert!(counter.update(Command::Increment));
+    assert_eq!(counter.value(), 1);
+    let records = counter.logger().sink().records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].level, Level::Info);
+    assert_eq!(records[0].field("kind"), Some("Increment"));
+    assert_eq!(records[0].field("old"), Some("0"));
+    assert_eq!(records[0].field("new"), Some("1"));
+}
+
+#[test]
+fn decrement_subtracts_step() {
+    let mut counter = counter(Config::default());
+    assert!(counter.update(Command::Decrement));
+    assert_eq!(counter.value(), -5);
+    let records = counter.logger().sink().records();
+    assert_eq!(records[0].field("kind"), Some("Decrement"));
+    assert_eq!(records[0].field("new"), Some("-5"));
+}
+
+#[test]
+fn custom_steps() {
+    let mut counter = counter(Config {
+        initial: 10,
+        increment: 3,
+        decrement: 2,
+        ..Config::default()
+    });
+    counter.update(Command::Increment);
+    counter.update(Command::Decrement);
+    assert_eq!(counter.value(), 11);
+}
+
+#[test]
+fn bounds_clamp_value() {
+    let mut counter = counter(Config {
+        initial: 100,
+        min: Some(-3),
+        max: Some(2),
+        ..Config::default()
+    });
+    assert_eq!(counter.value(), 2);
+    assert!(!counter.update(Command::Increment));
+    counter.update(Command::Decrement);
+    assert_eq!(counter.value(), -3);
+}
+
+#[test]
+fn nested_bulk_applies_every_action() {
+    let mut counter = counter(Config::default());
+    counter.update(Command::Bulk(vec![
+        Command::Increment,
+        Command::Bulk(vec![Command::Increment, Command::Decrement]),
+        Command::Increment,
+    ]));
+    assert_eq!(counter.value(), -2);
+    let records = counter.logger().sink().records();
+    let applied = records.iter().filter(|r| r.message == "applied").count();
+    assert_eq!(applied, 4);
+    let outer = records.back().unwrap();
+    assert_eq!(outer.message, "bulk applied");
+    assert_eq!(outer.field("count"), Some("3"));
+    assert_eq!(outer.field("old"), Some("0"));
+    assert_eq!(outer.field("new"), Some("-2"));
+}
+
+#[test]
+fn level_filter_applies_at_runtime() {
+    let mut counter = counter(Config::default());
+    counter.logger_mut().set_level(Level::Warn);
+    counter.update(Command::Increment);
+    assert!(counter.logger().sink().records().is_empty());
+    counter.logger_mut().set_level(Level::Info);
+    counter.update(Command::Bulk(vec![Command::Increment]));
+    assert_eq!(counter.logger().sink().records().len(), 1);
+}
+
+#[test]
+fn bulk_undoes_as_one_step() {
+    let mut counter = counter(Config::default());
+    counter.update(Command::Increment);
+    counter.update(Command::Bulk(vec![
+        Command::Increment,
+        Command::Bulk(vec![Command::Increment, Command::Increment]),
+    ]));
+    assert_eq!(counter.value(), 4);
+
+    assert!(counter.undo());
+    assert_eq!(counter.value(), 1);
+    assert!(counter.redo());
+    assert_eq!(counter.value(), 4);
+    assert!(!counter.redo());
+}
+
+#[test]
+fn configure_clamps_into_new_bounds() {
+    let mut counter = counter(Config::default());
+    counter.update(Command::Decrement);
+    assert!(counter.configure(Config {
+        min: Some(0),
+        ..Config::default()
+    }));
+    assert_eq!(counter.value(), 0);
+}
+
+#[test]
+fn undo_is_a_change_other_replicas_keep() {
+    let mut counter = counter(Config::default());
+    counter.update(Command::Increment);
+    counter.update(Command::Increment);
+    let shared = counter.state().counter.clone();
+
+    let mut remote = shared.clone();
+    remote.add("b", 10);
+    assert!(counter.undo());
+    assert_eq!(counter.value(), 1);
+    assert!(counter.merge(&remote));
+    assert_eq!(counter.value(), 11);
+
+    // The other replica sees the undo once it merges ours back.
+    remote.merge(&counter.state().counter.delta(&shared));
+    assert_eq!(remote.value(), 11);
+}
+
+#[test]
+fn merge_reports_only_new_changes() {
+    let mut counter = counter(Config::default());
+    counter.update(Command::Increment);
+    let own = counter.state().counter.clone();
+    assert!(!counter.merge(&own));
+    assert!(!counter.merge(&PnCounter::new()));
+}
+
+#[test]
+fn set_is_one_undoable_step() {
+    let mut counter = counter(Config {
+        max: Some(50),
+        ..Config::default()
+    });
+    assert!(counter.set(42));
+    assert_eq!(counter.value(), 42);
+    assert!(!counter.set(42));
+    assert!(counter.set(100));
+    assert_eq!(counter.value(), 50);
+    assert!(counter.undo());
+    assert_eq!(counter.value(), 42);
+    let records = counter.logger().sink().records();
+    assert_eq!(records[0].message, "set");
+    assert_eq!(records[0].field("new"), Some("42"));
+}
+
+#[test]
+fn overflow_errors_leave_the_counter_alone() {
+    let mut counter = counter(Config {
+        overflow: Overflow::Error,
+        ..Config::default()
+    });
+    counter.set(i64::MAX);
+    let err = counter.try_update(Command::Increment).unwrap_err();
+    assert_eq!(err.value, i64::MAX);
+    assert_eq!(counter.value(), i64::MAX);
+    assert!(!counter.update(Command::Increment));
+    assert_eq!(counter.logger().sink().records().back().unwrap().message, "overflow");
+}
+
+#[test]
+fn unbounded_values_undo_exactly() {
+    let mut counter = counter(Config {
+        overflow: Overflow::Unbounded,
+        ..Config::default()
+    });
+    counter.set(i64::MAX);
+    assert!(counter.update(Command::Increment));
+    assert!(counter.update(Command::Increment));
+    assert_eq!(counter.total().to_string(), "9223372036854775809");
+    assert_eq!(counter.value(), i64::MAX);
+    assert!(counter.undo());
+    assert_eq!(counter.total().to_string(), "9223372036854775808");
+
+    // Switching to a bounded policy pulls the value back into range.
+    assert!(counter.configure(Config::default()));
+    assert_eq!(counter.total(), Total::from(i64::MAX));
+}
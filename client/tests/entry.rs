@@ -0,0 +1,79 @@
+extern crate client;
+
+use client::counter::{Config, Overflow, Total};
+use client::entry::{parse, EntryError};
+
+fn bounded() -> Config {
+    Config {
+        min: Some(-10),
+        max: Some(10),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn absolute_values() {
+    let config = Config::default();
+    assert_eq!(parse("42", &Total::from(7), &config), Ok(Total::from(42)));
+    assert_eq!(parse("  0 ", &Total::from(7), &config), Ok(Total::from(0)));
+    assert_eq!(parse("=-3", &Total::from(7), &config), Ok(Total::from(-3)));
+}
+
+#[test]
+fn relative_adjustments() {
+    let config = Config::default();
+    assert_eq!(parse("+10", &Total::from(5), &config), Ok(Total::from(15)));
+    assert_eq!(parse("-3", &Total::from(5), &config), Ok(Total::from(2)));
+}
+
+#[test]
+fn non_numbers_are_rejected() {
+    let config = Config::default();
+    assert_eq!(parse("", &Total::from(0), &config), Err(EntryError::Empty));
+    assert_eq!(parse("=", &Total::from(0), &config), Err(EntryError::Empty));
+    assert_eq!(parse("abc", &Total::from(0), &config), Err(EntryError::NotANumber("abc".into())));
+    assert_eq!(parse("+", &Total::from(0), &config), Err(EntryError::NotANumber("+".into())));
+    assert_eq!(parse("--3", &Total::from(0), &config), Err(EntryError::NotANumber("--3".into())));
+    assert_eq!(parse("1.5", &Total::from(0), &config), Err(EntryError::NotANumber("1.5".into())));
+    assert_eq!(
+        parse("x", &Total::from(0), &config).unwrap_err().to_string(),
+        "`x` isn't a whole number"
+    );
+}
+
+#[test]
+fn out_of_range_values_are_rejected() {
+    let err = parse("11", &Total::from(0), &bounded()).unwrap_err();
+    assert_eq!(
+        err,
+        EntryError::OutOfRange {
+            value: Total::from(11),
+            min: Some(-10),
+            max: Some(10),
+        }
+    );
+    assert_eq!(err.to_string(), "11 is outside -10..=10");
+    assert!(parse("-5", &Total::from(-6), &bounded()).is_err());
+    assert_eq!(parse("+5", &Total::from(5), &bounded()), Ok(Total::from(10)));
+}
+
+#[test]
+fn overflow_is_reported() {
+    let config = Config::default();
+    assert_eq!(parse("9223372036854775808", &Total::from(0), &config), Err(EntryError::Overflow));
+    assert_eq!(parse("+1", &Total::from(i64::MAX), &config), Err(EntryError::Overflow));
+    assert_eq!(parse(&"9".repeat(60), &Total::from(0), &config), Err(EntryError::Overflow));
+    assert_eq!(parse("-1", &Total::from(i64::MIN + 1), &config), Ok(Total::from(i64::MIN)));
+}
+
+#[test]
+fn unbounded_counters_take_any_size() {
+    let config = Config {
+        overflow: Overflow::Unbounded,
+        ..Config::default()
+    };
+    let huge = parse(&"9".repeat(30), &Total::from(0), &config).unwrap();
+    assert_eq!(huge.to_string(), "9".repeat(30));
+    let more = parse("+1", &huge, &config).unwrap();
+    assert_eq!(more.to_string(), format!("1{}", "0".repeat(30)));
+}
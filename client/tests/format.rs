@@ -0,0 +1,57 @@
+extern crate client;
+
+use client::format::{format, TimeFormat};
+
+// 2018-10-21T14:03:09.250Z
+const AT: f64 = 1_540_130_589_250.0;
+const MINUTE: f64 = 60_000.0;
+
+#[test]
+fn iso8601() {
+    assert_eq!(format(&TimeFormat::Iso8601, AT, AT), "2018-10-21T14:03:09Z");
+    assert_eq!(format(&TimeFormat::Iso8601, 0.0, 0.0), "1970-01-01T00:00:00Z");
+}
+
+#[test]
+fn iso8601_before_epoch() {
+    assert_eq!(
+        format(&TimeFormat::Iso8601, -1000.0, 0.0),
+        "1969-12-31T23:59:59Z"
+    );
+}
+
+#[test]
+fn leap_day() {
+    // 2020-02-29T12:00:00Z
+    let at = 1_582_977_600_000.0;
+    assert_eq!(format(&TimeFormat::Iso8601, at, at), "2020-02-29T12:00:00Z");
+}
+
+#[test]
+fn utc_offset() {
+    assert_eq!(
+        format(&TimeFormat::UtcOffset(330), AT, AT),
+        "2018-10-21T19:33:09+05:30"
+    );
+    assert_eq!(
+        format(&TimeFormat::UtcOffset(-15 * 60), AT, AT),
+        "2018-10-20T23:03:09-15:00"
+    );
+}
+
+#[test]
+fn locale_short_fallback() {
+    assert_eq!(format(&TimeFormat::LocaleShort, AT, AT), "21 Oct 2018 14:03");
+}
+
+#[test]
+fn relative() {
+    let relative = |now: f64| format(&TimeFormat::Relative, AT, now);
+    assert_eq!(relative(AT + 5_000.0), "just now");
+    assert_eq!(relative(AT + 30_000.0), "30 seconds ago");
+    assert_eq!(relative(AT + MINUTE), "1 minute ago");
+    assert_eq!(relative(AT + 3.0 * MINUTE), "3 minutes ago");
+    assert_eq!(relative(AT + 120.0 * MINUTE), "2 hours ago");
+    assert_eq!(relative(AT + 3.0 * 24.0 * 60.0 * MINUTE), "3 days ago");
+    assert_eq!(relative(AT - 3.0 * MINUTE), "in 3 minutes");
+}
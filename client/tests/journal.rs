@@ -0,0 +1,237 @@
+extern crate client;
+
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use client::counter::{Command, Config, Counter, PnCounter, Snapshot, State};
+use client::effects::Clock;
+use client::journal::{Event, Journal};
+use client::logging::{Level, Logger, NoopSink};
+
+/// Ticks one millisecond per reading.
+struct FakeClock(Cell<f64>);
+
+impl Clock for FakeClock {
+    fn now(&self) -> f64 {
+        let now = self.0.get();
+        self.0.set(now + 1.0);
+        now
+    }
+}
+
+fn counter(replica: &str) -> Counter<NoopSink> {
+    let logger = Logger::new("counter", Level::Error, NoopSink);
+    Counter::new(Config::default(), replica.into(), State::default(), logger)
+}
+
+/// Runs a few changes through a live counter, journaling each.
+fn session() -> (Counter<NoopSink>, Journal<FakeClock>) {
+    let mut live = counter("a");
+    let mut journal = Journal::new(FakeClock(Cell::new(1000.0)));
+    journal.record(Some("a"), Event::Snapshot(live.snapshot()));
+
+    let mut steps = vec![
+        Event::Command(Command::Increment),
+        Event::Command(Command::Bulk(vec![
+            Command::Increment,
+            Command::Bulk(vec![Command::Decrement]),
+        ])),
+        Event::Undo,
+        Event::Redo,
+        Event::Undo,
+    ];
+    let mut remote = PnCounter::new();
+    remote.add("b", 7);
+    steps.push(Event::Merge(remote));
+    steps.push(Event::Configure(Config {
+        min: Some(0),
+        ..Config::default()
+    }));
+    steps.push(Event::Command(Command::Decrement));
+
+    for event in steps {
+        let replica = match event {
+            Event::Merge(_) => None,
+            _ => Some("a"),
+        };
+        journal.record(replica, event.clone());
+        match event {
+            Event::Command(command) => {
+                live.update(command);
+            }
+            Event::Undo => {
+                live.undo();
+            }
+            Event::Redo => {
+                live.redo();
+            }
+            Event::Merge(other) => {
+                live.merge(&other);
+            }
+            Event::Configure(config) => {
+                live.configure(config);
+            }
+            _ => unreachable!(),
+        }
+    }
+    (live, journal)
+}
+
+#[test]
+fn bulk_children_get_their_own_entries() {
+    let (_, journal) = session();
+    let entries = journal.entries();
+    let bulk = &entries[2];
+    assert_eq!(bulk.seq, 2);
+    assert_eq!(bulk.parent, None);
+    let children: Vec<_> = entries.iter().filter(|entry| entry.parent.is_some()).collect();
+    assert_eq!(children.len(), 3);
+    assert_eq!(children[0].parent, Some(2));
+    assert_eq!(children[1].parent, Some(2));
+    assert_eq!(children[1].event, Event::Command(Command::Bulk(vec![Command::Decrement])));
+    assert_eq!(children[2].parent, Some(children[1].seq));
+    assert!(children.iter().all(|entry| entry.at == bulk.at));
+    for (idx, entry) in entries.iter().enumerate() {
+        assert_eq!(entry.seq, idx as u64);
+    }
+}
+
+#[test]
+fn replay_from_zero_rebuilds_the_state() {
+    let (live, journal) = session();
+    let mut replayed = counter("replayer");
+    journal.replay(&mut replayed);
+    assert_eq!(replayed.snapshot(), live.snapshot());
+    assert_eq!(replayed.replica(), "replayer");
+}
+
+#[test]
+fn replay_from_snapshot_rebuilds_the_state() {
+    let (mut live, mut journal) = session();
+    journal.record(Some("a"), Event::Snapshot(live.snapshot()));
+    journal.record(Some("a"), Event::Command(Command::Increment));
+    live.update(Command::Increment);
+
+    let mut replayed = counter("replayer");
+    journal.replay_from_snapshot(&mut replayed);
+    assert_eq!(replayed.snapshot(), live.snapshot());
+}
+
+#[test]
+fn replayed_total_leaves_the_live_counter_alone() {
+    let (mut live, journal) = session();
+    let ended_at = live.total();
+    live.update(Command::Increment);
+    live.update(Command::Increment);
+    let tallies = live.state().clone();
+    assert_eq!(journal.replayed_total(&Config::default()), ended_at);
+    assert_eq!(live.state(), &tallies);
+    // Moving back there only ever grows the live counter's tallies.
+    live.set(journal.replayed_total(&Config::default()));
+    assert_eq!(live.total(), ended_at);
+    assert_eq!(tallies.counter.delta(&live.state().counter), PnCounter::new());
+}
+
+#[test]
+fn trim_cuts_at_a_snapshot() {
+    let (live, mut journal) = session();
+    let first = journal.len();
+    journal.record(Some("a"), Event::Snapshot(live.snapshot()));
+    journal.record(Some("a"), Event::Command(Command::Increment));
+    assert_eq!(journal.since_snapshot(), 1);
+
+    // Nowhere to cut within the last two entries but the snapshot.
+    journal.trim(2);
+    assert_eq!(journal.len(), 2);
+    assert_eq!(journal.entries()[0].seq, first as u64);
+    let mut replayed = counter("replayer");
+    journal.replay_from_snapshot(&mut replayed);
+    assert_eq!(replayed.value(), live.value() + 1);
+
+    // Without a snapshot in reach, nothing goes.
+    journal.record(Some("a"), Event::Command(Command::Increment));
+    journal.trim(1);
+    assert_eq!(journal.len(), 3);
+}
+
+#[test]
+fn restore_checks_saved_entries() {
+    let (_, journal) = session();
+    let mut restored = Journal::new(FakeClock(Cell::new(0.0)));
+    restored.restore(journal.entries().to_vec()).unwrap();
+    assert_eq!(restored.entries(), journal.entries());
+
+    let mut gappy = journal.entries().to_vec();
+    gappy.remove(1);
+    let err = restored.restore(gappy).unwrap_err();
+    assert_eq!(err.line, 2);
+    assert_eq!(restored.entries(), journal.entries());
+}
+
+#[test]
+fn switching_channels_keeps_both_saved_journals() {
+    // Stands in for localStorage, one journal per channel.
+    let mut saved = HashMap::new();
+    let (_, mut journal) = session();
+    let first = journal.entries().to_vec();
+    saved.insert("a", first.clone());
+
+    // Another counter moves into this slot. Its journal is loaded before
+    // the reset goes in, so that's the one the reset lands in.
+    journal.switch(saved.get("b").cloned()).unwrap();
+    assert!(journal.is_empty());
+    journal.record(Some("a"), Event::Reset(State::default()));
+    journal.record(Some("a"), Event::Command(Command::Increment));
+    saved.insert("b", journal.entries().to_vec());
+
+    journal.switch(saved.get("a").cloned()).unwrap();
+    assert_eq!(journal.entries(), &first[..]);
+    assert_eq!(saved["b"].len(), 2);
+}
+
+#[test]
+fn json_lines_round_trip() {
+    let (live, journal) = session();
+    let text = journal.to_json_lines();
+    assert_eq!(text.lines().count(), journal.len());
+
+    let mut imported = Journal::new(FakeClock(Cell::new(0.0)));
+    imported.import(&text).unwrap();
+    assert_eq!(imported.entries(), journal.entries());
+    let mut replayed = counter("replayer");
+    imported.replay(&mut replayed);
+    assert_eq!(replayed.state(), live.state());
+
+    // New entries carry on from the imported sequence numbers.
+    let seq = imported.record(None, Event::Undo);
+    assert_eq!(seq, journal.len() as u64);
+}
+
+#[test]
+fn import_rejects_bad_lines() {
+    let (_, journal) = session();
+    let mut lines: Vec<String> = journal.to_json_lines().lines().map(String::from).collect();
+
+    let mut target = Journal::new(FakeClock(Cell::new(0.0)));
+    let garbled = format!("{}\nnot json\n", lines[0]);
+    assert_eq!(target.import(&garbled).unwrap_err().line, 2);
+
+    lines.remove(1);
+    let gap = lines.join("\n");
+    let err = target.import(&gap).unwrap_err();
+    assert_eq!(err.line, 2);
+    assert_eq!(err.to_string(), "line 2: expected entry 1, found 2");
+    assert!(target.is_empty());
+}
+
+#[test]
+fn snapshot_restores_history() {
+    let mut live = counter("a");
+    live.update(Command::Increment);
+    let snapshot = live.snapshot();
+    let mut other = counter("b");
+    other.restore(snapshot.clone());
+    assert!(other.undo());
+    assert_eq!(other.value(), 0);
+    assert_eq!(Snapshot::new(Config::default(), State::default()).state, State::default());
+}
@@ -0,0 +1,54 @@
+extern crate client;
+
+use client::keys::{is_typing, Chord, Keymap};
+
+#[test]
+fn parses_modifiers_and_keys() {
+    let chord = Chord::parse("Ctrl+Shift+Z").unwrap();
+    assert_eq!(chord, Chord::new("z", true, false, true, false));
+    assert_eq!(chord.to_string(), "Ctrl+Shift+Z");
+    assert_eq!(Chord::parse("Escape").unwrap().key, "Escape");
+    assert_eq!(Chord::parse("cmd+k").unwrap(), Chord::new("k", false, false, false, true));
+}
+
+#[test]
+fn plus_can_be_the_key() {
+    assert_eq!(Chord::parse("+").unwrap(), Chord::new("+", false, false, false, false));
+    assert_eq!(Chord::parse("Ctrl++").unwrap(), Chord::new("+", true, false, false, false));
+}
+
+#[test]
+fn shift_only_counts_for_letters() {
+    // `?` needs Shift on most layouts, but the binding shouldn't care.
+    assert_eq!(Chord::new("?", false, false, true, false), Chord::parse("?").unwrap());
+    assert_eq!(Chord::new("U", false, false, true, false), Chord::parse("Shift+u").unwrap());
+    assert!(Chord::new("U", false, false, true, false) != Chord::parse("u").unwrap());
+}
+
+#[test]
+fn rejects_bad_chords() {
+    assert!(Chord::parse("").is_err());
+    assert!(Chord::parse("Ctrl+").is_err());
+    assert_eq!(
+        Chord::parse("Hyper+x").unwrap_err(),
+        "unknown modifier `hyper` in `Hyper+x`"
+    );
+}
+
+#[test]
+fn keymap_looks_up_and_rebinds() {
+    let mut keymap = Keymap::new().with("+", 1).with("u", 2);
+    assert_eq!(keymap.get(&Chord::new("+", false, false, true, false)), Some(&1));
+    assert_eq!(keymap.get(&Chord::new("x", false, false, false, false)), None);
+    keymap.bind(Chord::parse("U").unwrap(), 3);
+    assert_eq!(keymap.get(&Chord::parse("u").unwrap()), Some(&3));
+    assert_eq!(keymap.bindings().len(), 2);
+}
+
+#[test]
+fn typing_targets() {
+    assert!(is_typing("INPUT", false));
+    assert!(is_typing("textarea", false));
+    assert!(is_typing("DIV", true));
+    assert!(!is_typing("BUTTON", false));
+}
@@ -0,0 +1,43 @@
+extern crate client;
+
+use client::lease::{Leases, LEASE_MS};
+
+#[test]
+fn live_tabs_get_different_ids() {
+    let mut leases = Leases::default();
+    let first = leases.claim("one", 0.0, "tab-a".into());
+    let second = leases.claim("two", 1.0, "tab-b".into());
+    assert_eq!(first, "tab-a");
+    assert_eq!(second, "tab-b");
+}
+
+#[test]
+fn reloads_reuse_the_released_id() {
+    let mut leases = Leases::default();
+    let id = leases.claim("before", 0.0, "tab-a".into());
+    leases.release(&id, "before");
+    assert_eq!(leases.claim("after", 1.0, "tab-b".into()), "tab-a");
+    assert!(!leases.holds(&id, "before"));
+}
+
+#[test]
+fn lapsed_ids_are_reused_and_their_old_holder_told() {
+    let mut leases = Leases::default();
+    let id = leases.claim("asleep", 0.0, "tab-a".into());
+    assert!(leases.renew(&id, "asleep", LEASE_MS / 2.0));
+    assert_eq!(leases.claim("new", LEASE_MS, "tab-b".into()), "tab-b");
+
+    let later = LEASE_MS * 2.0;
+    assert_eq!(leases.claim("newer", later, "tab-c".into()), "tab-a");
+    assert!(!leases.renew(&id, "asleep", later));
+    assert!(leases.holds(&id, "newer"));
+    assert_eq!(leases.add("asleep", later, "tab-d".into()), "tab-d");
+}
+
+#[test]
+fn renewing_a_lapsed_id_nobody_took_keeps_it() {
+    let mut leases = Leases::default();
+    let id = leases.claim("one", 0.0, "tab-a".into());
+    assert!(leases.renew(&id, "one", LEASE_MS * 3.0));
+    assert_eq!(leases.claim("two", LEASE_MS * 3.5, "tab-b".into()), "tab-b");
+}
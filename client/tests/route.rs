@@ -0,0 +1,46 @@
+extern crate client;
+
+use client::route::{self, Route};
+
+#[test]
+fn parses_every_page() {
+    assert_eq!(Route::parse(""), Route::Counters);
+    assert_eq!(Route::parse("#"), Route::Counters);
+    assert_eq!(Route::parse("#/"), Route::Counters);
+    assert_eq!(Route::parse("#/counters"), Route::Counters);
+    assert_eq!(Route::parse("#/counters/12"), Route::Counter(12));
+    assert_eq!(Route::parse("#/history"), Route::History);
+    assert_eq!(Route::parse("#/settings/"), Route::Settings);
+    assert_eq!(Route::parse("settings"), Route::Settings);
+}
+
+#[test]
+fn unknown_paths_are_kept() {
+    assert_eq!(Route::parse("#/nope"), Route::NotFound("nope".into()));
+    assert_eq!(Route::parse("#/counters/x"), Route::NotFound("counters/x".into()));
+    assert_eq!(Route::parse("#/history/more"), Route::NotFound("history/more".into()));
+}
+
+#[test]
+fn hrefs_round_trip() {
+    let routes = vec![
+        Route::Counters,
+        Route::Counter(3),
+        Route::History,
+        Route::Settings,
+        Route::NotFound("somewhere/else".into()),
+    ];
+    for route in routes {
+        assert_eq!(Route::parse(&route.href()), route);
+    }
+    assert_eq!(Route::Counter(3).href(), "#/counters/3");
+    assert_eq!(Route::Counters.href(), "#/");
+}
+
+#[test]
+fn queries_ride_along() {
+    let hash = "#/counters/3?value=42&step=5";
+    assert_eq!(Route::parse(hash), Route::Counter(3));
+    assert_eq!(route::query(hash), "value=42&step=5");
+    assert_eq!(route::query("#/history"), "");
+}
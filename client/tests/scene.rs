@@ -0,0 +1,153 @@
+extern crate client;
+
+use client::paint::{Align, Painter};
+use client::scene::{Node, Region, Retained, Transform};
+
+/// Records each drawing call as a short line of text.
+#[derive(Default)]
+struct Recorder {
+    calls: Vec<String>,
+}
+
+impl Painter for Recorder {
+    fn clear_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.calls.push(format!("clear {} {} {} {}", x, y, width, height));
+    }
+
+    fn polyline(&mut self, points: &[(f64, f64)], color: &str, _: f64) {
+        self.calls.push(format!("line {} {}", points.len(), color));
+    }
+
+    fn fill_rect(&mut self, _: f64, _: f64, _: f64, _: f64, color: &str) {
+        self.calls.push(format!("rect {}", color));
+    }
+
+    fn fill_polygon(&mut self, points: &[(f64, f64)], color: &str) {
+        self.calls.push(format!("polygon {} {}", points.len(), color));
+    }
+
+    fn fill_circle(&mut self, _: f64, _: f64, _: f64, color: &str) {
+        self.calls.push(format!("circle {}", color));
+    }
+
+    fn stroke_circle(&mut self, _: f64, _: f64, _: f64, color: &str, _: f64) {
+        self.calls.push(format!("ring {}", color));
+    }
+
+    fn text(&mut self, text: &str, _: f64, _: f64, _: Align, _: &str) {
+        self.calls.push(format!("text {}", text));
+    }
+
+    fn clip(&mut self, _: f64, _: f64, _: f64, _: f64) {
+        self.calls.push("clip".into());
+    }
+
+    fn unclip(&mut self) {
+        self.calls.push("unclip".into());
+    }
+}
+
+fn scene(label: &str) -> Node<&'static str> {
+    Node::group(vec![
+        Node::rect(0.0, 0.0, 100.0, 50.0).fill("gray").tag("background"),
+        Node::group(vec![
+            Node::circle(0.0, 0.0, 10.0).fill("red").tag("button"),
+            Node::text(label, 0.0, 0.0, Align::Center),
+        ])
+        .transform(Transform::translate(200.0, 25.0)),
+    ])
+}
+
+#[test]
+fn transforms_compose_inner_first() {
+    let moved = Transform::translate(10.0, 0.0).then(&Transform::scale(2.0, 3.0));
+    assert_eq!(moved.apply((1.0, 1.0)), (12.0, 3.0));
+    let (x, y) = Transform::rotate(::std::f64::consts::FRAC_PI_2).apply((1.0, 0.0));
+    assert!(x.abs() < 1e-9 && (y - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn hit_finds_topmost_tagged_shape() {
+    let mut retained = Retained::new();
+    retained.update(&scene("0"));
+    assert_eq!(retained.hit(50.0, 25.0), Some("background"));
+    assert_eq!(retained.hit(205.0, 25.0), Some("button"));
+    assert_eq!(retained.hit(150.0, 25.0), None);
+
+    let layered = Node::group(vec![
+        Node::rect(0.0, 0.0, 10.0, 10.0).fill("gray").tag("below"),
+        Node::rect(0.0, 0.0, 10.0, 10.0).fill("gray").tag("above"),
+        Node::text("untagged", 5.0, 5.0, Align::Center),
+    ]);
+    retained.update(&layered);
+    assert_eq!(retained.hit(5.0, 5.0), Some("above"));
+}
+
+#[test]
+fn hit_follows_rotation() {
+    let mut retained = Retained::new();
+    let diamond = Node::group(vec![Node::rect(-10.0, -10.0, 20.0, 20.0).fill("blue").tag("diamond")])
+        .transform(Transform::rotate(::std::f64::consts::FRAC_PI_4))
+        .transform(Transform::translate(50.0, 50.0));
+    retained.update(&diamond);
+    assert_eq!(retained.hit(50.0, 36.0), Some("diamond"));
+    assert_eq!(retained.hit(41.0, 41.0), None);
+}
+
+#[test]
+fn only_changed_shapes_are_dirty() {
+    let mut retained = Retained::new();
+    let first = retained.update(&scene("0"));
+    assert_eq!(first.len(), 2);
+    assert!(retained.update(&scene("0")).is_empty());
+
+    let dirty = retained.update(&scene("10"));
+    assert_eq!(dirty.len(), 1);
+    let region = dirty[0];
+    assert!(region.x > 100.0, "the background didn't change: {:?}", region);
+
+    let mut painter = Recorder::default();
+    retained.paint(&mut painter, &dirty);
+    assert_eq!(painter.calls[0], "clip");
+    assert!(painter.calls.contains(&"text 10".to_string()));
+    assert!(painter.calls.contains(&"circle red".to_string()));
+    assert!(!painter.calls.iter().any(|call| call.starts_with("polygon")));
+    assert_eq!(painter.calls.last().map(String::as_str), Some("unclip"));
+}
+
+#[test]
+fn invalidate_repaints_everything() {
+    let mut retained = Retained::new();
+    retained.update(&scene("0"));
+    retained.invalidate();
+    let dirty = retained.update(&scene("0"));
+    let all = dirty.iter().fold(dirty[0], |all, region| all.union(region));
+    assert!(all.intersects(&Region {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    }));
+    assert!(all.intersects(&Region {
+        x: 205.0,
+        y: 25.0,
+        width: 1.0,
+        height: 1.0,
+    }));
+}
+
+#[test]
+fn strokes_and_open_paths_are_drawn() {
+    let mut retained: Retained<()> = Retained::new();
+    let scene = Node::group(vec![
+        Node::path(vec![(0.0, 0.0), (10.0, 10.0), (20.0, 0.0)], false).stroke("black", 2.0),
+        Node::rect(30.0, 0.0, 10.0, 10.0).stroke("green", 1.0),
+        Node::circle(60.0, 5.0, 5.0).stroke("blue", 1.0),
+    ]);
+    let dirty = retained.update(&scene);
+    let mut painter = Recorder::default();
+    retained.paint(&mut painter, &dirty);
+    assert!(painter.calls.contains(&"line 3 black".to_string()));
+    assert!(painter.calls.contains(&"line 5 green".to_string()));
+    assert!(painter.calls.contains(&"ring blue".to_string()));
+}
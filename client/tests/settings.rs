@@ -0,0 +1,70 @@
+extern crate client;
+
+use client::counter::Config;
+use client::logging::Level;
+use client::settings::{Invalid, Settings};
+
+#[test]
+fn defaults_match_the_counter() {
+    let settings = Settings::default();
+    assert!(settings.validate().is_empty());
+    assert_eq!(settings.configure(&Config::default()), Config::default());
+}
+
+#[test]
+fn defaults_follow_the_configured_steps() {
+    let config = Config {
+        increment: 10,
+        decrement: 3,
+        ..Config::default()
+    };
+    let settings = Settings::for_config(&config);
+    assert_eq!((settings.increment, settings.decrement), (10, 3));
+    assert_eq!(settings.configure(&config), config);
+    assert_eq!(settings.theme, Settings::default().theme);
+}
+
+#[test]
+fn validation_names_each_bad_field() {
+    let settings = Settings {
+        increment: 0,
+        decrement: 5_000_000,
+        theme: "neon".into(),
+        ..Settings::default()
+    };
+    let problems = settings.validate();
+    let fields: Vec<_> = problems.iter().map(|problem| problem.field).collect();
+    assert_eq!(fields, vec!["increment", "decrement", "theme"]);
+    assert_eq!(
+        problems[2],
+        Invalid {
+            field: "theme",
+            reason: "`neon` isn't a theme".into(),
+        }
+    );
+}
+
+#[test]
+fn json_round_trips() {
+    let settings = Settings {
+        increment: 2,
+        decrement: 3,
+        confirm_decrement: false,
+        log_level: Level::Debug,
+        theme: "dark".into(),
+    };
+    assert_eq!(Settings::from_json(&settings.to_json()), Ok(settings));
+    let partial = Settings::from_json(r#"{"increment": 4}"#).unwrap();
+    assert_eq!(partial.increment, 4);
+    assert_eq!(partial.decrement, Settings::default().decrement);
+}
+
+#[test]
+fn imports_refuse_bad_files() {
+    assert!(Settings::from_json("not json").is_err());
+    assert!(Settings::from_json(r#"{"colour": "red"}"#).is_err());
+    assert_eq!(
+        Settings::from_json(r#"{"decrement": -1}"#),
+        Err("decrement: must be between 1 and 1000000".to_string())
+    );
+}
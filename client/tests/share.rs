@@ -0,0 +1,76 @@
+extern crate client;
+
+use client::counter::{Config, Overflow, Total};
+use client::share::{decode, encode, ParamError, Params};
+
+#[test]
+fn reads_a_shared_counter() {
+    let params = decode("value=42&step=5", &Config::default()).unwrap();
+    assert_eq!(params.value, Some(Total::from(42)));
+    let config = params.configure(&Config::default());
+    assert_eq!((config.increment, config.decrement), (5, 5));
+    assert_eq!(decode("", &Config::default()), Ok(Params::default()));
+}
+
+#[test]
+fn round_trips_through_encode() {
+    let base = Config::default();
+    let config = Config {
+        increment: 2,
+        decrement: 3,
+        min: Some(-10),
+        max: Some(100),
+        overflow: Overflow::Wrap,
+        ..base.clone()
+    };
+    let query = encode(&Total::from(-4), &config, &base);
+    assert_eq!(query, "value=-4&increment=2&decrement=3&min=-10&max=100&overflow=wrap");
+    let params = decode(&query, &base).unwrap();
+    assert_eq!(params.value, Some(Total::from(-4)));
+    assert_eq!(params.configure(&base), config);
+
+    // Settings that match the counter's own are left out.
+    assert_eq!(encode(&Total::from(7), &base, &base), "value=7");
+}
+
+#[test]
+fn refuses_malformed_queries() {
+    let base = Config::default();
+    assert_eq!(decode("value", &base), Err(ParamError::Malformed("value".into())));
+    assert_eq!(decode("value=", &base), Err(ParamError::Malformed("value=".into())));
+    assert_eq!(
+        decode("value=%3Cscript%3E", &base),
+        Err(ParamError::Malformed("value=%3Cscript%3E".into()))
+    );
+    assert_eq!(decode(&"a".repeat(600), &base), Err(ParamError::TooLong));
+    assert_eq!(decode("colour=red", &base), Err(ParamError::Unknown("colour".into())));
+}
+
+#[test]
+fn refuses_tampered_values() {
+    let base = Config::default();
+    assert_eq!(decode("value=1&value=2", &base), Err(ParamError::Repeated("value".into())));
+    assert_eq!(decode("step=1&increment=2", &base), Err(ParamError::Repeated("increment".into())));
+    assert_eq!(
+        decode("step=1x", &base),
+        Err(ParamError::Invalid {
+            key: "step".into(),
+            value: "1x".into(),
+        })
+    );
+    for query in &["step=-1", "step=0", "increment=1000001"] {
+        match decode(query, &base) {
+            Err(ParamError::Setting(_)) => {}
+            other => panic!("{}: {:?}", query, other),
+        }
+    }
+    let err = decode("decrement=0", &base).unwrap_err();
+    assert_eq!(err.to_string(), "decrement: must be between 1 and 1000000");
+    assert!(decode("step=1000000", &base).is_ok());
+    assert!(decode("overflow=sometimes", &base).is_err());
+    assert!(decode("min=5&max=1", &base).is_err());
+    assert!(decode("value=50&max=10", &base).is_err());
+    let err = decode("value=99999999999999999999", &base).unwrap_err();
+    assert_eq!(err.to_string(), "the value is too large for this counter");
+    assert!(decode("value=99999999999999999999&overflow=unbounded", &base).is_ok());
+}
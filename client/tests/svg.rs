@@ -0,0 +1,42 @@
+extern crate client;
+
+use client::chart::{LineChart, Point};
+use client::paint::{Align, Painter};
+use client::scene::Node;
+use client::svg::{self, SvgPainter};
+use client::theme::DARK;
+
+#[test]
+fn chart_exports_what_the_canvas_draws() {
+    let chart = LineChart {
+        points: vec![Point { at: 0.0, value: 1 }, Point { at: 5000.0, value: 4 }],
+        theme: DARK,
+    };
+    let doc = svg::render(&chart, 400.0, 200.0);
+    assert!(doc.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="400" height="200""#));
+    assert!(doc.ends_with("</svg>\n"));
+    assert!(doc.contains(&format!(r#"stroke="{}" stroke-width="2""#, DARK.accent)));
+    assert!(doc.contains(">5s</text>"));
+    // No tooltip without a pointer.
+    assert!(!doc.contains("<circle"));
+}
+
+#[test]
+fn scene_exports_with_balanced_clips() {
+    let scene: Node<()> = Node::group(vec![
+        Node::circle(10.0, 10.0, 5.0).fill("red"),
+        Node::text("a < b", 20.0, 10.0, Align::Start),
+    ]);
+    let doc = svg::render(&scene, 100.0, 50.0);
+    assert!(doc.contains(r#"<circle cx="10" cy="10" r="5" fill="red"/>"#));
+    assert!(doc.contains(">a &lt; b</text>"));
+    assert_eq!(doc.matches("<g ").count(), doc.matches("</g>").count());
+}
+
+#[test]
+fn attributes_are_escaped() {
+    let mut painter = SvgPainter::new(10.0, 10.0);
+    painter.fill_rect(0.0, 0.0, 1.5, 2.0, "\"><script>");
+    let doc = painter.finish();
+    assert!(doc.contains(r#"width="1.5" height="2" fill="&quot;&gt;&lt;script&gt;""#));
+}
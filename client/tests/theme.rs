@@ -0,0 +1,48 @@
+extern crate client;
+
+use client::theme::{self, Theme, DARK, LIGHT, SYSTEM, THEMES};
+
+#[test]
+fn themes_are_found_by_name() {
+    assert_eq!(theme::find("dark"), Some(&DARK));
+    assert_eq!(theme::find(SYSTEM), None);
+    assert_eq!(theme::names(), vec!["system", "light", "dark", "high-contrast"]);
+}
+
+#[test]
+fn every_theme_sets_the_same_properties() {
+    let names = |theme: &Theme| -> Vec<String> {
+        theme
+            .custom_properties()
+            .lines()
+            .map(|line| line.trim().split(':').next().unwrap().to_string())
+            .collect()
+    };
+    for theme in THEMES.iter() {
+        assert_eq!(names(theme), names(&LIGHT), "{}", theme.name);
+    }
+    assert!(LIGHT.custom_properties().contains("  --background: #fafafa;\n"));
+}
+
+#[test]
+fn stylesheet_follows_the_system_unless_told_otherwise() {
+    let css = theme::stylesheet();
+    let media = css.find("@media (prefers-color-scheme: dark)").unwrap();
+    let explicit = css.find(":root[data-theme=\"light\"]").unwrap();
+    // Later rules of the same specificity win, and explicit choices are
+    // more specific anyway.
+    assert!(media < explicit);
+    for theme in THEMES.iter() {
+        assert!(css.contains(&format!(":root[data-theme=\"{}\"]", theme.name)));
+    }
+    assert!(css.contains("nav.menu"));
+}
+
+#[test]
+fn drawings_resolve_the_system_theme() {
+    assert_eq!(theme::resolve(SYSTEM, false), &LIGHT);
+    assert_eq!(theme::resolve(SYSTEM, true), &DARK);
+    assert_eq!(theme::resolve("high-contrast", false).name, "high-contrast");
+    assert_eq!(theme::resolve("light", true), &LIGHT);
+    assert_eq!(Theme::default(), LIGHT);
+}
@@ -0,0 +1,69 @@
+extern crate client;
+
+use client::timeline::{Action, Timeline};
+
+fn timeline() -> Timeline<i64> {
+    let mut timeline = Timeline::new(10);
+    timeline.record("Increment", 0, 1);
+    timeline.record("Bulk", 0, 3);
+    timeline.record("Increment", 1, 2);
+    timeline.record("Increment", 1, 3);
+    timeline
+}
+
+#[test]
+fn starts_live() {
+    let timeline = timeline();
+    assert!(timeline.is_live());
+    assert_eq!(timeline.shown(), None);
+    assert_eq!(timeline.moments()[2].depth, 1);
+}
+
+#[test]
+fn steps_back_and_forward() {
+    let mut timeline = timeline();
+    timeline.handle(Action::Back);
+    assert_eq!(timeline.cursor(), Some(2));
+    assert_eq!(timeline.shown(), Some(&2));
+    timeline.handle(Action::Back);
+    timeline.handle(Action::Back);
+    timeline.handle(Action::Back);
+    assert_eq!(timeline.cursor(), Some(0));
+    timeline.handle(Action::Forward);
+    assert_eq!(timeline.shown(), Some(&3));
+    timeline.handle(Action::Forward);
+    timeline.handle(Action::Forward);
+    assert!(timeline.is_live());
+}
+
+#[test]
+fn preview_and_resume() {
+    let mut timeline = timeline();
+    timeline.handle(Action::Preview(0));
+    assert_eq!(timeline.shown(), Some(&1));
+    timeline.record("Decrement", 0, -2);
+    assert_eq!(timeline.shown(), Some(&1));
+    timeline.handle(Action::Preview(99));
+    assert_eq!(timeline.shown(), Some(&-2));
+    timeline.handle(Action::Resume);
+    assert!(timeline.is_live());
+}
+
+#[test]
+fn drops_oldest_past_the_limit() {
+    let mut timeline = Timeline::new(2);
+    timeline.record("a", 0, 1);
+    timeline.record("b", 0, 2);
+    timeline.handle(Action::Preview(1));
+    timeline.record("c", 0, 3);
+    assert_eq!(timeline.moments().len(), 2);
+    assert_eq!(timeline.moments()[0].label, "b");
+    assert_eq!(timeline.shown(), Some(&2));
+}
+
+#[test]
+fn empty_timeline_ignores_actions() {
+    let mut timeline: Timeline<i64> = Timeline::new(2);
+    timeline.handle(Action::Back);
+    assert!(timeline.is_live());
+}
@@ -0,0 +1,114 @@
+//! The JSON REST API under `/api/`.
+//!
+//! - `GET /api/counters` lists every counter.
+//! - `GET /api/counters/{name}` returns one counter.
+//! - `POST /api/counters/{name}/increment` and `.../decrement` step it.
+//! - `POST /api/counters/{name}/bulk` applies a JSON array of commands, e.g.
+//!   `["Increment", {"Bulk": ["Decrement"]}]`.
+//!
+//! Names are percent-decoded, so `/api/counters/My%20Counter` is the
+//! counter a sync client knows as `My Counter`. Commands the overflow policy
+//! refuses get a 422 with the reason.
+
+use std::borrow::Cow;
+use std::io::{self, Cursor};
+
+use percent_encoding::percent_decode;
+use serde::Serialize;
+use serde_json;
+use tiny_http::{Header, Method, Request, Response, StatusCode};
+
+use shared::{Command, Counter, ServerMessage, Total};
+use store::Store;
+use sync::Hub;
+
+pub type Reply = Response<Cursor<Vec<u8>>>;
+
+#[derive(Serialize)]
+struct Error<'a> {
+    error: &'a str,
+}
+
+pub fn handle(store: &mut Store, hub: &Hub, request: &mut Request) -> Reply {
+    let mut body = String::new();
+    if request.as_reader().read_to_string(&mut body).is_err() {
+        return error(400, "unreadable request body");
+    }
+    let method = request.method().clone();
+    route(store, hub, &method, request.url(), &body)
+}
+
+/// Answers a request for `url`, e.g. `/api/counters/a/increment`, whose
+/// body has already been read.
+pub fn route(store: &mut Store, hub: &Hub, method: &Method, url: &str, body: &str) -> Reply {
+    let path = url.split('?').next().unwrap_or("");
+    let decoded: Option<Vec<String>> = path
+        .trim_matches('/')
+        .split('/')
+        .skip(1)
+        .map(|segment| percent_decode(segment.as_bytes()).decode_utf8().ok().map(Cow::into_owned))
+        .collect();
+    let decoded = match decoded {
+        Some(decoded) => decoded,
+        None => return error(400, "the path isn't valid UTF-8"),
+    };
+    let segments: Vec<&str> = decoded.iter().map(String::as_str).collect();
+
+    match (method.clone(), &segments[..]) {
+        (Method::Get, &["counters"]) => json(200, &store.list()),
+        (Method::Get, &["counters", name]) => match store.get(name) {
+            Some(state) => json(200, &counter(name, state.total())),
+            None => error(404, "no such counter"),
+        },
+        (Method::Post, &["counters", name, "increment"]) => apply(store, hub, name, &Command::Increment),
+        (Method::Post, &["counters", name, "decrement"]) => apply(store, hub, name, &Command::Decrement),
+        (Method::Post, &["counters", name, "bulk"]) => match serde_json::from_str(body) {
+            Ok(commands) => apply(store, hub, name, &Command::Bulk(commands)),
+            Err(_) => error(400, "expected a JSON array of commands"),
+        },
+        (_, &["counters"]) | (_, &["counters", _]) | (_, &["counters", _, _]) => {
+            error(405, "method not allowed")
+        }
+        _ => error(404, "no such endpoint"),
+    }
+}
+
+fn apply(store: &mut Store, hub: &Hub, name: &str, command: &Command) -> Reply {
+    if name.is_empty() {
+        return error(400, "counter names can't be empty");
+    }
+    match store.apply(name, command) {
+        Ok(state) => {
+            hub.broadcast(&ServerMessage::Updated {
+                counter: name.to_string(),
+                state: state.counter.clone(),
+            });
+            json(200, &counter(name, state.total()))
+        }
+        Err(ref err) if err.kind() == io::ErrorKind::InvalidInput => error(422, &err.to_string()),
+        Err(err) => {
+            eprintln!("failed to save counters: {}", err);
+            error(500, "failed to save counters")
+        }
+    }
+}
+
+fn counter(name: &str, value: Total) -> Counter {
+    Counter {
+        name: name.to_string(),
+        value,
+    }
+}
+
+fn json<T: Serialize>(status: u16, body: &T) -> Reply {
+    let body = serde_json::to_vec(body).expect("API types always serialize");
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is valid");
+    Response::from_data(body)
+        .with_status_code(StatusCode(status))
+        .with_header(content_type)
+}
+
+fn error(status: u16, message: &str) -> Reply {
+    json(status, &Error { error: message })
+}
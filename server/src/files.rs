@@ -0,0 +1,64 @@
+//! Static files from the client's `cargo web deploy` output.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use percent_encoding::percent_decode;
+use tiny_http::{Header, Request, Response, StatusCode};
+
+use api::Reply;
+
+pub fn serve(root: &Path, request: &Request) -> Reply {
+    let path = match resolve(root, request.url()) {
+        Some(path) => path,
+        None => return not_found(),
+    };
+    match fs::read(&path) {
+        Ok(data) => {
+            let content_type = Header::from_bytes(&b"Content-Type"[..], content_type(&path))
+                .expect("static header is valid");
+            Response::from_data(data).with_header(content_type)
+        }
+        Err(_) => not_found(),
+    }
+}
+
+/// Maps a request URL onto a file under `root`, refusing anything that
+/// tries to climb out of it. Segments are percent-decoded before they are
+/// checked, so `%2e%2e` and `%2f` are caught as well as `..` and `/`.
+pub fn resolve(root: &Path, url: &str) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    let raw = url.split('?').next().unwrap_or("");
+    for segment in raw.split('/').filter(|segment| !segment.is_empty()) {
+        let segment = percent_decode(segment.as_bytes()).decode_utf8().ok()?;
+        if segment.contains(&['/', '\\', '\0'][..]) {
+            return None;
+        }
+        let mut components = Path::new(&*segment).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => path.push(&*segment),
+            _ => return None,
+        }
+    }
+    if path.is_dir() {
+        path.push("index.html");
+    }
+    Some(path)
+}
+
+fn content_type(path: &Path) -> &'static [u8] {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("html") => b"text/html; charset=utf-8",
+        Some("js") => b"application/javascript",
+        Some("wasm") => b"application/wasm",
+        Some("css") => b"text/css",
+        Some("json") => b"application/json",
+        Some("png") => b"image/png",
+        Some("svg") => b"image/svg+xml",
+        _ => b"application/octet-stream",
+    }
+}
+
+fn not_found() -> Reply {
+    Response::from_string("Not Found").with_status_code(StatusCode(404))
+}
@@ -0,0 +1,16 @@
+//! The counter server's parts, kept apart from `main` so they can be
+//! tested.
+
+extern crate percent_encoding;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate shared;
+extern crate tiny_http;
+extern crate tungstenite;
+
+pub mod api;
+pub mod files;
+pub mod store;
+pub mod sync;
@@ -0,0 +1,58 @@
+//! Serves the counter REST API alongside the client's `cargo web deploy`
+//! output.
+//!
+//! Configured through the environment:
+//!
+//! - `ADDR`: address to listen on, `127.0.0.1:8000` by default.
+//! - `DATA`: JSON file the counters are kept in, `counters.json` by default.
+//! - `STATIC`: directory of static files, `../client/target/deploy` by default.
+//! - `SYNC_ADDR`: address of the WebSocket sync endpoint, `127.0.0.1:8001` by
+//!   default.
+//! - `OVERFLOW`: what commands do at the `i64` limits: `saturate` (the
+//!   default), `wrap`, `error` or `unbounded`.
+
+extern crate server;
+extern crate shared;
+extern crate tiny_http;
+
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use server::store::Store;
+use server::sync::{self, Hub};
+use server::{api, files};
+use shared::Config;
+use tiny_http::Server;
+
+fn main() {
+    let addr = env::var("ADDR").unwrap_or_else(|_| "127.0.0.1:8000".into());
+    let data = env::var("DATA").unwrap_or_else(|_| "counters.json".into());
+    let root = env::var("STATIC").unwrap_or_else(|_| "../client/target/deploy".into());
+    let sync_addr = env::var("SYNC_ADDR").unwrap_or_else(|_| "127.0.0.1:8001".into());
+    let config = Config {
+        overflow: match env::var("OVERFLOW") {
+            Ok(name) => name.parse().unwrap_or_else(|err| panic!("OVERFLOW: {}", err)),
+            Err(_) => Default::default(),
+        },
+        ..Config::default()
+    };
+
+    let store = Store::open(PathBuf::from(data), config).expect("failed to open the counter store");
+    let store = Arc::new(Mutex::new(store));
+    let hub = Hub::default();
+    sync::listen(&sync_addr, store.clone(), hub.clone()).expect("failed to bind the sync endpoint");
+    let server = Server::http(&addr[..]).expect("failed to bind the server");
+    println!("Listening on http://{} (sync on ws://{})", addr, sync_addr);
+
+    for mut request in server.incoming_requests() {
+        let response = if request.url().starts_with("/api/") {
+            api::handle(&mut store.lock().unwrap(), &hub, &mut request)
+        } else {
+            files::serve(root.as_ref(), &request)
+        };
+        if let Err(err) = request.respond(response) {
+            eprintln!("failed to send response: {}", err);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Counters kept in memory and mirrored to a JSON file after every change.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::PathBuf;
+
+use serde_json::{self, Value};
+use shared::{Command, Config, Counter, PnCounter, State};
+
+/// Schema version written by this build.
+const VERSION: u32 = 2;
+
+/// The replica the server's own changes (REST calls, `Apply` messages) are
+/// attributed to.
+pub const REPLICA: &str = "server";
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u32,
+    counters: T,
+}
+
+pub struct Store {
+    path: PathBuf,
+    config: Config,
+    counters: BTreeMap<String, State>,
+}
+
+impl Store {
+    /// Loads the counters from `path`, starting empty if the file doesn't
+    /// exist yet. Commands are applied under `config`.
+    pub fn open(path: PathBuf, config: Config) -> io::Result<Store> {
+        let counters = match File::open(&path) {
+            Ok(file) => {
+                let envelope: Envelope<Value> = serde_json::from_reader(BufReader::new(file))?;
+                migrate(envelope)?
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => return Err(err),
+        };
+        Ok(Store {
+            path,
+            config,
+            counters,
+        })
+    }
+
+    pub fn list(&self) -> Vec<Counter> {
+        self.counters
+            .iter()
+            .map(|(name, state)| Counter {
+                name: name.clone(),
+                value: state.total(),
+            })
+            .collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&State> {
+        self.counters.get(name)
+    }
+
+    /// Applies `command` to the named counter, creating it if needed, and
+    /// writes the result to disk. A command refused by the overflow policy
+    /// fails with `io::ErrorKind::InvalidInput` and changes nothing.
+    pub fn apply(&mut self, name: &str, command: &Command) -> io::Result<State> {
+        let state = {
+            let config = &self.config;
+            let state = self
+                .counters
+                .entry(name.to_string())
+                .or_insert_with(|| State::initial(config.initial));
+            state
+                .apply(REPLICA, config, command)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+            state.clone()
+        };
+        self.save()?;
+        Ok(state)
+    }
+
+    /// Folds a client's changes into the named counter, creating it if
+    /// needed, and writes the result to disk.
+    pub fn merge(&mut self, name: &str, delta: &PnCounter) -> io::Result<State> {
+        let state = {
+            let state = self.counters.entry(name.to_string()).or_default();
+            state.counter.merge(delta);
+            state.clone()
+        };
+        self.save()?;
+        Ok(state)
+    }
+
+    /// Writes to a sibling file first so a crash never leaves a torn store.
+    fn save(&self) -> io::Result<()> {
+        let tmp = self.path.with_extension("json.tmp");
+        {
+            let writer = BufWriter::new(File::create(&tmp)?);
+            let envelope = Envelope {
+                version: VERSION,
+                counters: &self.counters,
+            };
+            serde_json::to_writer_pretty(writer, &envelope)?;
+        }
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+/// Reads the counters in whatever layout `envelope.version` used.
+fn migrate(envelope: Envelope<Value>) -> io::Result<BTreeMap<String, State>> {
+    match envelope.version {
+        // Plain integers, from before counters were CRDTs.
+        1 => {
+            let values: BTreeMap<String, i64> = serde_json::from_value(envelope.counters)?;
+            Ok(values
+                .into_iter()
+                .map(|(name, value)| (name, State::from_value(REPLICA, value)))
+                .collect())
+        }
+        VERSION => Ok(serde_json::from_value(envelope.counters)?),
+        version => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported store version {}", version),
+        )),
+    }
+}
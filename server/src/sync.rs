@@ -0,0 +1,137 @@
+//! WebSocket endpoint that keeps every open client in step. Clients send
+//! `ClientMessage`s; every change is broadcast back as a `ServerMessage`.
+
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde_json;
+use shared::{ClientMessage, ServerMessage};
+use tungstenite::{self, Message};
+
+use store::Store;
+
+/// How long a connection waits for input before checking for broadcasts.
+const POLL: Duration = Duration::from_millis(50);
+
+pub type SharedStore = Arc<Mutex<Store>>;
+
+/// Fans messages out to every connected client.
+#[derive(Clone, Default)]
+pub struct Hub {
+    clients: Arc<Mutex<Vec<Sender<String>>>>,
+}
+
+impl Hub {
+    pub fn broadcast(&self, message: &ServerMessage) {
+        let text = serde_json::to_string(message).expect("protocol types always serialize");
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|client| client.send(text.clone()).is_ok());
+    }
+
+    fn join(&self) -> mpsc::Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        self.clients.lock().unwrap().push(tx);
+        rx
+    }
+}
+
+/// Accepts WebSocket connections on `addr` in the background.
+pub fn listen(addr: &str, store: SharedStore, hub: Hub) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let store = store.clone();
+                    let hub = hub.clone();
+                    thread::spawn(move || serve(stream, &store, &hub));
+                }
+                Err(err) => eprintln!("failed to accept sync connection: {}", err),
+            }
+        }
+    });
+    Ok(())
+}
+
+fn serve(stream: TcpStream, store: &SharedStore, hub: &Hub) {
+    let mut socket = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(err) => {
+            eprintln!("sync handshake failed: {}", err);
+            return;
+        }
+    };
+    if let Err(err) = socket.get_ref().set_read_timeout(Some(POLL)) {
+        eprintln!("failed to configure sync connection: {}", err);
+        return;
+    }
+    let broadcasts = hub.join();
+
+    loop {
+        match socket.read_message() {
+            Ok(Message::Text(text)) => {
+                if let Some(reply) = handle(&text, store, hub) {
+                    if socket.write_message(Message::Text(reply)).is_err() {
+                        return;
+                    }
+                }
+            }
+            Ok(Message::Close(_)) => return,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(ref err))
+                if err.kind() == io::ErrorKind::WouldBlock || err.kind() == io::ErrorKind::TimedOut => {}
+            Err(tungstenite::Error::ConnectionClosed) | Err(tungstenite::Error::AlreadyClosed) => return,
+            Err(err) => {
+                eprintln!("sync connection failed: {}", err);
+                return;
+            }
+        }
+        for text in broadcasts.try_iter() {
+            if socket.write_message(Message::Text(text)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Acts on one client message. Returns a reply meant only for the sender.
+fn handle(text: &str, store: &SharedStore, hub: &Hub) -> Option<String> {
+    let message = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(err) => {
+            eprintln!("ignoring malformed sync message: {}", err);
+            return None;
+        }
+    };
+    let mut store = store.lock().unwrap();
+    let result = match message {
+        ClientMessage::Subscribe(counter) => {
+            return store.get(&counter).map(|state| {
+                let message = ServerMessage::Updated {
+                    counter,
+                    state: state.counter.clone(),
+                };
+                serde_json::to_string(&message).expect("protocol types always serialize")
+            });
+        }
+        ClientMessage::Apply { counter, command } => store
+            .apply(&counter, &command)
+            .map(|state| (counter, state)),
+        ClientMessage::Merge { counter, delta } => store
+            .merge(&counter, &delta)
+            .map(|state| (counter, state)),
+    };
+    match result {
+        Ok((counter, state)) => hub.broadcast(&ServerMessage::Updated {
+            counter,
+            state: state.counter,
+        }),
+        Err(ref err) if err.kind() == io::ErrorKind::InvalidInput => eprintln!("refused sync command: {}", err),
+        Err(err) => eprintln!("failed to save counters: {}", err),
+    }
+    None
+}
@@ -0,0 +1,101 @@
+extern crate server;
+extern crate serde_json;
+extern crate shared;
+extern crate tiny_http;
+
+use std::env;
+use std::fs;
+
+use serde_json::Value;
+use server::api::{self, Reply};
+use server::store::Store;
+use server::sync::Hub;
+use shared::{Config, Overflow};
+use tiny_http::{HTTPVersion, Method};
+
+fn store(name: &str, config: Config) -> Store {
+    let path = env::temp_dir().join(format!("server-api-{}-{}.json", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    Store::open(path, config).unwrap()
+}
+
+/// The reply's status code and JSON body.
+fn read(reply: Reply) -> (u16, Value) {
+    let mut raw = Vec::new();
+    reply
+        .raw_print(&mut raw, HTTPVersion(1, 1), &[], false, None)
+        .unwrap();
+    let raw = String::from_utf8(raw).unwrap();
+    let status = raw[9..12].parse().unwrap();
+    let body = &raw[raw.find("\r\n\r\n").unwrap() + 4..];
+    (status, serde_json::from_str(body).unwrap())
+}
+
+fn call(store: &mut Store, method: Method, url: &str, body: &str) -> (u16, Value) {
+    read(api::route(store, &Hub::default(), &method, url, body))
+}
+
+#[test]
+fn counters_are_stepped_and_read_back() {
+    let mut store = store("step", Config::default());
+    let (status, body) = call(&mut store, Method::Post, "/api/counters/a/increment", "");
+    assert_eq!((status, body["value"].clone()), (200, Value::from(1)));
+    let (status, body) = call(&mut store, Method::Post, "/api/counters/a/decrement", "");
+    assert_eq!((status, body["value"].clone()), (200, Value::from(-4)));
+    let (status, body) = call(&mut store, Method::Post, "/api/counters/a/bulk", r#"["Increment", {"Bulk": ["Increment"]}]"#);
+    assert_eq!((status, body["value"].clone()), (200, Value::from(-2)));
+    let (status, body) = call(&mut store, Method::Get, "/api/counters/a", "");
+    assert_eq!(status, 200);
+    assert_eq!(body, serde_json::json!({"name": "a", "value": -2}));
+    let (status, body) = call(&mut store, Method::Get, "/api/counters?pretty", "");
+    assert_eq!(status, 200);
+    assert_eq!(body, serde_json::json!([{"name": "a", "value": -2}]));
+}
+
+#[test]
+fn names_are_percent_decoded() {
+    let mut store = store("decode", Config::default());
+    call(&mut store, Method::Post, "/api/counters/My%20Counter/increment", "");
+    assert_eq!(store.get("My Counter").unwrap().value(), 1);
+    let (status, body) = call(&mut store, Method::Get, "/api/counters/My%20Counter", "");
+    assert_eq!((status, body["name"].clone()), (200, Value::from("My Counter")));
+    let (status, _) = call(&mut store, Method::Get, "/api/counters/%FF", "");
+    assert_eq!(status, 400);
+}
+
+#[test]
+fn unknown_counters_and_endpoints_are_not_found() {
+    let mut store = store("missing", Config::default());
+    assert_eq!(call(&mut store, Method::Get, "/api/counters/nope", "").0, 404);
+    assert_eq!(call(&mut store, Method::Get, "/api/elsewhere", "").0, 404);
+    assert_eq!(call(&mut store, Method::Post, "/api/counters/a/increment/twice", "").0, 404);
+}
+
+#[test]
+fn wrong_methods_are_not_allowed() {
+    let mut store = store("method", Config::default());
+    assert_eq!(call(&mut store, Method::Post, "/api/counters", "").0, 405);
+    assert_eq!(call(&mut store, Method::Delete, "/api/counters/a", "").0, 405);
+    assert_eq!(call(&mut store, Method::Get, "/api/counters/a/increment", "").0, 405);
+}
+
+#[test]
+fn bad_bulk_bodies_are_refused() {
+    let mut store = store("bulk", Config::default());
+    let (status, _) = call(&mut store, Method::Post, "/api/counters/a/bulk", "Increment");
+    assert_eq!(status, 400);
+    assert!(store.get("a").is_none());
+}
+
+#[test]
+fn overflow_refusals_are_unprocessable() {
+    let config = Config {
+        initial: i64::MAX,
+        overflow: Overflow::Error,
+        ..Config::default()
+    };
+    let mut store = store("overflow", config);
+    let (status, body) = call(&mut store, Method::Post, "/api/counters/a/increment", "");
+    assert_eq!(status, 422);
+    assert!(body["error"].as_str().unwrap().contains("past the limits"));
+}
@@ -0,0 +1,31 @@
+extern crate server;
+
+use std::path::Path;
+
+use server::files::resolve;
+
+#[test]
+fn plain_paths_stay_under_the_root() {
+    let root = Path::new("/srv/static");
+    assert_eq!(resolve(root, "/app.js?v=2"), Some(root.join("app.js")));
+    assert_eq!(resolve(root, "/css//site.css"), Some(root.join("css").join("site.css")));
+    assert_eq!(resolve(root, "/My%20File.txt"), Some(root.join("My File.txt")));
+}
+
+#[test]
+fn climbing_out_is_refused_even_when_encoded() {
+    let root = Path::new("/srv/static");
+    for url in &[
+        "/../secret",
+        "/css/../../secret",
+        "/%2e%2e/secret",
+        "/%2E%2E%2Fsecret",
+        "/css%2f..%2f..%2fsecret",
+        "/..%5csecret",
+        "/./app.js",
+        "/%00app.js",
+        "/%ff",
+    ] {
+        assert_eq!(resolve(root, url), None, "{}", url);
+    }
+}
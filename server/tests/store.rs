@@ -0,0 +1,103 @@
+extern crate server;
+extern crate shared;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use server::store::{Store, REPLICA};
+use shared::{Command, Config, Overflow, PnCounter};
+
+/// A fresh file path for one test to keep its store in.
+fn scratch(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("server-store-{}-{}.json", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn apply_creates_counters_at_the_initial_value() {
+    let config = Config {
+        initial: 10,
+        ..Config::default()
+    };
+    let mut store = Store::open(scratch("apply"), config).unwrap();
+    assert!(store.get("a").is_none());
+    assert_eq!(store.apply("a", &Command::Increment).unwrap().value(), 11);
+    let bulk = Command::Bulk(vec![Command::Decrement, Command::Increment]);
+    assert_eq!(store.apply("a", &bulk).unwrap().value(), 7);
+    assert_eq!(store.list().len(), 1);
+}
+
+#[test]
+fn merge_folds_in_other_replicas() {
+    let mut store = Store::open(scratch("merge"), Config::default()).unwrap();
+    store.apply("a", &Command::Increment).unwrap();
+    let tab = PnCounter::from_value("tab-1", 4);
+    assert_eq!(store.merge("a", &tab).unwrap().value(), 5);
+    // Merging the same changes twice counts them once.
+    assert_eq!(store.merge("a", &tab).unwrap().value(), 5);
+    assert_eq!(store.merge("b", &tab).unwrap().value(), 4);
+}
+
+#[test]
+fn counters_survive_reopening() {
+    let path = scratch("reopen");
+    {
+        let mut store = Store::open(path.clone(), Config::default()).unwrap();
+        store.apply("a", &Command::Increment).unwrap();
+        store.merge("a", &PnCounter::from_value("tab-1", -3)).unwrap();
+    }
+    let store = Store::open(path.clone(), Config::default()).unwrap();
+    let state = store.get("a").unwrap();
+    assert_eq!(state.value(), -2);
+    let mut expected = PnCounter::from_value(REPLICA, 1);
+    expected.add("tab-1", -3);
+    assert_eq!(state.counter, expected);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn version_one_stores_are_migrated() {
+    let path = scratch("migrate");
+    fs::write(&path, r#"{"version": 1, "counters": {"a": 5, "b": -2}}"#).unwrap();
+    let mut store = Store::open(path.clone(), Config::default()).unwrap();
+    assert_eq!(store.get("a").unwrap().value(), 5);
+    assert_eq!(store.get("b").unwrap().value(), -2);
+    assert_eq!(store.apply("a", &Command::Increment).unwrap().value(), 6);
+    // Saving writes the current version.
+    let saved = fs::read_to_string(&path).unwrap();
+    assert!(saved.contains(r#""version": 2"#));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn unknown_versions_are_refused() {
+    let path = scratch("future");
+    fs::write(&path, r#"{"version": 99, "counters": {}}"#).unwrap();
+    let err = Store::open(path.clone(), Config::default()).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn overflow_is_refused_without_changing_anything() {
+    let config = Config {
+        initial: i64::MAX,
+        overflow: Overflow::Error,
+        ..Config::default()
+    };
+    let path = scratch("overflow");
+    let mut store = Store::open(path.clone(), config).unwrap();
+    let err = store.apply("a", &Command::Increment).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(store.get("a").unwrap().value(), i64::MAX);
+    // Fine until the last step.
+    let mut steps = vec![Command::Decrement];
+    steps.extend(vec![Command::Increment; 6]);
+    let bulk = Command::Bulk(steps);
+    assert!(store.apply("a", &bulk).is_err());
+    assert_eq!(store.get("a").unwrap().value(), i64::MAX);
+    let _ = fs::remove_file(path);
+}
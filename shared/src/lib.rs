@@ -0,0 +1,290 @@
+//! Counter commands and state shared by the client, the server, and anything
+//! else that speaks the counter protocol. Builds for both wasm32 and native.
+
+extern crate num_bigint;
+extern crate num_traits;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+
+mod pn_counter;
+mod total;
+
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+pub use pn_counter::PnCounter;
+pub use total::Total;
+
+/// Something that can be done to a counter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Command {
+    Increment,
+    Decrement,
+    Bulk(Vec<Command>),
+}
+
+impl Command {
+    /// The variant's name, for logs.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            Command::Increment => "Increment",
+            Command::Decrement => "Decrement",
+            Command::Bulk(_) => "Bulk",
+        }
+    }
+}
+
+/// What a command does when it would take the value past the `i64` limits.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum Overflow {
+    /// Stops at the limit.
+    #[default]
+    Saturate,
+    /// Carries on from the other limit, like two's complement arithmetic.
+    Wrap,
+    /// Refuses the command, leaving the value where it was.
+    Error,
+    /// Never overflows: the value is kept with arbitrary precision.
+    Unbounded,
+}
+
+impl Overflow {
+    pub const ALL: [Overflow; 4] = [Overflow::Saturate, Overflow::Wrap, Overflow::Error, Overflow::Unbounded];
+
+    /// The lowercase name `from_str` reads back.
+    pub fn name(self) -> &'static str {
+        match self {
+            Overflow::Saturate => "saturate",
+            Overflow::Wrap => "wrap",
+            Overflow::Error => "error",
+            Overflow::Unbounded => "unbounded",
+        }
+    }
+}
+
+impl FromStr for Overflow {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        for &policy in &Overflow::ALL {
+            if policy.name() == text {
+                return Ok(policy);
+            }
+        }
+        Err(format!("unknown overflow policy `{}`", text))
+    }
+}
+
+/// A command refused under `Overflow::Error`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverflowError {
+    /// The value before the command.
+    pub value: i64,
+    /// The change that didn't fit.
+    pub change: i128,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.change < 0 { '-' } else { '+' };
+        write!(
+            f,
+            "{} {} {} is past the limits of a 64-bit counter",
+            self.value,
+            sign,
+            self.change.abs()
+        )
+    }
+}
+
+impl error::Error for OverflowError {}
+
+/// How commands move a counter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// Starting value, used when nothing has been saved yet.
+    pub initial: i64,
+    /// Amount added by `Command::Increment`.
+    pub increment: i64,
+    /// Amount subtracted by `Command::Decrement`.
+    pub decrement: i64,
+    /// Lowest value the counter may reach.
+    pub min: Option<i64>,
+    /// Highest value the counter may reach.
+    pub max: Option<i64>,
+    /// What happens at the `i64` limits.
+    pub overflow: Overflow,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            initial: 0,
+            increment: 1,
+            decrement: 5,
+            min: None,
+            max: None,
+            overflow: Overflow::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Pulls `value` back inside the configured bounds.
+    pub fn clamp(&self, value: i64) -> i64 {
+        let value = self.min.map_or(value, |min| value.max(min));
+        self.max.map_or(value, |max| value.min(max))
+    }
+
+    /// Like `clamp`, but for a value of any size. Unless the counter is
+    /// `Overflow::Unbounded`, that includes pulling it inside the `i64`
+    /// limits.
+    pub fn clamp_total(&self, value: &Total) -> Total {
+        if self.overflow != Overflow::Unbounded {
+            return Total::from(self.clamp(value.saturating_i64()));
+        }
+        let mut value = value.clone();
+        if let Some(min) = self.min.map(Total::from) {
+            if value < min {
+                value = min;
+            }
+        }
+        if let Some(max) = self.max.map(Total::from) {
+            if value > max {
+                value = max;
+            }
+        }
+        value
+    }
+
+    /// Where moving `value` by `change` ends up under the overflow policy,
+    /// inside the configured bounds.
+    pub fn step(&self, value: &Total, change: i128) -> Result<Total, OverflowError> {
+        if self.overflow == Overflow::Unbounded {
+            return Ok(self.clamp_total(&(value + &Total::from_i128(change))));
+        }
+        let value = value.saturating_i64();
+        let target = i128::from(value) + change;
+        let target = match self.overflow {
+            Overflow::Wrap => target as i64,
+            _ if target > i128::from(i64::MAX) || target < i128::from(i64::MIN) => {
+                if self.overflow == Overflow::Error {
+                    return Err(OverflowError { value, change });
+                }
+                if target < 0 {
+                    i64::MIN
+                } else {
+                    i64::MAX
+                }
+            }
+            _ => target as i64,
+        };
+        Ok(Total::from(self.clamp(target)))
+    }
+}
+
+/// The replica a new counter's starting value is credited to. Every copy of
+/// a counter seeds this same tally, so merging them counts it once rather
+/// than once per copy.
+pub const INITIAL: &str = "initial";
+
+/// Everything about a counter that survives a reload.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct State {
+    pub counter: PnCounter,
+}
+
+impl State {
+    /// A state at `value`, attributed entirely to `replica`.
+    pub fn from_value(replica: &str, value: i64) -> Self {
+        State {
+            counter: PnCounter::from_value(replica, value),
+        }
+    }
+
+    /// A new counter starting at `value`, credited to `INITIAL`.
+    pub fn initial(value: i64) -> Self {
+        State::from_value(INITIAL, value)
+    }
+
+    /// The value, saturating at the `i64` limits.
+    pub fn value(&self) -> i64 {
+        self.counter.value()
+    }
+
+    /// The exact value, which only leaves the `i64` range for
+    /// `Overflow::Unbounded` counters.
+    pub fn total(&self) -> Total {
+        self.counter.total()
+    }
+
+    /// Applies `command` as changes made by `replica`.
+    pub fn apply(&mut self, replica: &str, config: &Config, command: &Command) -> Result<(), OverflowError> {
+        self.apply_with(replica, config, command, &mut |_, _, _| {})
+    }
+
+    /// Like `apply`, but calls `observe(command, old, new)` after every
+    /// step, including once for each `Bulk` after its children have run.
+    ///
+    /// A `Bulk` is all or nothing: if any step overflows under
+    /// `Overflow::Error`, the state is left as it was, although `observe`
+    /// will already have seen the steps before it.
+    pub fn apply_with<F>(
+        &mut self,
+        replica: &str,
+        config: &Config,
+        command: &Command,
+        observe: &mut F,
+    ) -> Result<(), OverflowError>
+    where
+        F: FnMut(&Command, &Total, &Total),
+    {
+        let old = self.total();
+        let change = match *command {
+            Command::Increment => i128::from(config.increment),
+            Command::Decrement => -i128::from(config.decrement),
+            Command::Bulk(ref list) => {
+                let mut scratch = self.clone();
+                for command in list {
+                    scratch.apply_with(replica, config, command, observe)?;
+                }
+                *self = scratch;
+                observe(command, &old, &self.total());
+                return Ok(());
+            }
+        };
+        let target = config.step(&old, change)?;
+        self.counter.add(replica, &target - &old);
+        observe(command, &old, &target);
+        Ok(())
+    }
+}
+
+/// A named counter as reported by the server.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Counter {
+    pub name: String,
+    pub value: Total,
+}
+
+/// Sent by a client over the sync socket.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ClientMessage {
+    /// Asks for the named counter's current value.
+    Subscribe(String),
+    /// Applies a command on the server's behalf.
+    Apply { counter: String, command: Command },
+    /// Folds a client's changes into the server's copy.
+    Merge { counter: String, delta: PnCounter },
+}
+
+/// Sent by the server over the sync socket.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ServerMessage {
+    /// A counter changed, or a client asked for it. Clients merge `state`
+    /// into their own copy.
+    Updated { counter: String, state: PnCounter },
+}
@@ -0,0 +1,89 @@
+//! A positive-negative counter CRDT. Every replica only ever grows its own
+//! increment and decrement tallies, so replicas can apply changes
+//! concurrently and merge in any order without losing updates.
+
+use std::collections::BTreeMap;
+
+use total::Total;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct PnCounter {
+    /// Total added by each replica. Never negative.
+    p: BTreeMap<String, Total>,
+    /// Total subtracted by each replica. Never negative.
+    n: BTreeMap<String, Total>,
+}
+
+impl PnCounter {
+    pub fn new() -> Self {
+        PnCounter::default()
+    }
+
+    /// A counter at `value`, attributed entirely to `replica`.
+    pub fn from_value(replica: &str, value: i64) -> Self {
+        let mut counter = PnCounter::new();
+        counter.add(replica, value);
+        counter
+    }
+
+    /// The current value, saturating at the `i64` limits.
+    pub fn value(&self) -> i64 {
+        self.total().saturating_i64()
+    }
+
+    /// The exact current value.
+    pub fn total(&self) -> Total {
+        let p = self.p.values().fold(Total::zero(), |sum, tally| &sum + tally);
+        self.n.values().fold(p, |sum, tally| &sum - tally)
+    }
+
+    /// Records `replica` moving the counter by `delta`.
+    pub fn add<T: Into<Total>>(&mut self, replica: &str, delta: T) {
+        let delta = delta.into();
+        if delta.is_zero() {
+            return;
+        }
+        let (tallies, amount) = if delta.is_negative() {
+            (&mut self.n, -delta)
+        } else {
+            (&mut self.p, delta)
+        };
+        let tally = tallies.entry(replica.to_string()).or_default();
+        *tally = &*tally + &amount;
+    }
+
+    /// Folds `other` into `self`. Commutative, associative and idempotent.
+    pub fn merge(&mut self, other: &PnCounter) {
+        merge_tallies(&mut self.p, &other.p);
+        merge_tallies(&mut self.n, &other.n);
+    }
+
+    /// The smallest counter that, merged into `since`, gives `self`; i.e.
+    /// only the tallies that grew. Cheaper to ship than the whole state.
+    pub fn delta(&self, since: &PnCounter) -> PnCounter {
+        PnCounter {
+            p: grown_tallies(&self.p, &since.p),
+            n: grown_tallies(&self.n, &since.n),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.p.is_empty() && self.n.is_empty()
+    }
+}
+
+fn merge_tallies(into: &mut BTreeMap<String, Total>, from: &BTreeMap<String, Total>) {
+    for (replica, tally) in from {
+        let entry = into.entry(replica.clone()).or_default();
+        if tally > entry {
+            *entry = tally.clone();
+        }
+    }
+}
+
+fn grown_tallies(now: &BTreeMap<String, Total>, since: &BTreeMap<String, Total>) -> BTreeMap<String, Total> {
+    now.iter()
+        .filter(|&(replica, tally)| Some(tally) > since.get(replica))
+        .map(|(replica, tally)| (replica.clone(), tally.clone()))
+        .collect()
+}
@@ -0,0 +1,133 @@
+//! An exact integer of any size, for counters that must never overflow.
+
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+
+/// Serializes as a plain number while it fits in an `i64` and as a string of
+/// digits beyond that. Reads either, so data written before counters could
+/// grow this large still loads.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Total(BigInt);
+
+impl Total {
+    pub fn zero() -> Self {
+        Total(BigInt::zero())
+    }
+
+    /// Not a `From` impl, so plain integer literals still pick
+    /// `From<i64>`.
+    pub(crate) fn from_i128(value: i128) -> Self {
+        Total(BigInt::from(value))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0 < BigInt::zero()
+    }
+
+    /// The value, or `None` if it doesn't fit in an `i64`.
+    pub fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    /// The value, pulled to the nearest `i64` limit if it doesn't fit.
+    pub fn saturating_i64(&self) -> i64 {
+        match self.to_i64() {
+            Some(value) => value,
+            None if self.is_negative() => i64::MIN,
+            None => i64::MAX,
+        }
+    }
+}
+
+impl From<i64> for Total {
+    fn from(value: i64) -> Self {
+        Total(BigInt::from(value))
+    }
+}
+
+impl FromStr for Total {
+    type Err = ();
+
+    /// Reads an optionally signed run of decimal digits.
+    fn from_str(text: &str) -> Result<Self, ()> {
+        text.parse().map(Total).map_err(|_| ())
+    }
+}
+
+impl fmt::Display for Total {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'a> Add<&'a Total> for &'a Total {
+    type Output = Total;
+
+    fn add(self, other: &Total) -> Total {
+        Total(&self.0 + &other.0)
+    }
+}
+
+impl<'a> Sub<&'a Total> for &'a Total {
+    type Output = Total;
+
+    fn sub(self, other: &Total) -> Total {
+        Total(&self.0 - &other.0)
+    }
+}
+
+impl Neg for Total {
+    type Output = Total;
+
+    fn neg(self) -> Total {
+        Total(-self.0)
+    }
+}
+
+impl Serialize for Total {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.to_i64() {
+            Some(value) => serializer.serialize_i64(value),
+            None => serializer.serialize_str(&self.0.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Total {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TotalVisitor)
+    }
+}
+
+struct TotalVisitor;
+
+impl<'de> Visitor<'de> for TotalVisitor {
+    type Value = Total;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an integer or a string of digits")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Total, E> {
+        Ok(Total::from(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Total, E> {
+        Ok(Total(BigInt::from(value)))
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<Total, E> {
+        text.parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(text), &self))
+    }
+}
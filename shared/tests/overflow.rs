@@ -0,0 +1,82 @@
+extern crate serde_json;
+extern crate shared;
+
+use shared::{Command, Config, Overflow, OverflowError, PnCounter, State, Total};
+
+fn config(overflow: Overflow) -> Config {
+    Config {
+        increment: 10,
+        decrement: 10,
+        overflow,
+        ..Config::default()
+    }
+}
+
+fn near_max() -> State {
+    State::from_value("a", i64::MAX - 5)
+}
+
+#[test]
+fn saturate_stops_at_the_limit() {
+    let mut state = near_max();
+    state.apply("a", &config(Overflow::Saturate), &Command::Increment).unwrap();
+    assert_eq!(state.value(), i64::MAX);
+}
+
+#[test]
+fn wrap_carries_on_from_the_other_limit() {
+    let mut state = near_max();
+    let policy = config(Overflow::Wrap);
+    state.apply("a", &policy, &Command::Increment).unwrap();
+    assert_eq!(state.value(), i64::MIN + 4);
+    state.apply("a", &policy, &Command::Decrement).unwrap();
+    assert_eq!(state.value(), i64::MAX - 5);
+    assert_eq!(state.total(), Total::from(i64::MAX - 5));
+}
+
+#[test]
+fn error_refuses_the_whole_bulk() {
+    let mut state = near_max();
+    let policy = config(Overflow::Error);
+    let bulk = Command::Bulk(vec![Command::Decrement, Command::Increment, Command::Increment]);
+    let err = state.apply("a", &policy, &bulk).unwrap_err();
+    assert_eq!(
+        err,
+        OverflowError {
+            value: i64::MAX - 5,
+            change: 10,
+        }
+    );
+    assert_eq!(err.to_string(), "9223372036854775802 + 10 is past the limits of a 64-bit counter");
+    assert_eq!(state, near_max());
+}
+
+#[test]
+fn unbounded_counts_past_i64() {
+    let mut state = near_max();
+    let policy = config(Overflow::Unbounded);
+    state.apply("a", &policy, &Command::Bulk(vec![Command::Increment, Command::Increment])).unwrap();
+    assert_eq!(state.value(), i64::MAX);
+    assert_eq!(state.total().to_string(), "9223372036854775822");
+
+    // Pulled back into range once the policy changes.
+    let clamped = config(Overflow::Saturate).clamp_total(&state.total());
+    assert_eq!(clamped, Total::from(i64::MAX));
+}
+
+#[test]
+fn large_tallies_serialize_as_strings() {
+    let mut counter = PnCounter::new();
+    counter.add("a", i64::MAX);
+    assert_eq!(serde_json::to_string(&counter).unwrap(), r#"{"p":{"a":9223372036854775807},"n":{}}"#);
+    counter.add("a", i64::MAX);
+    counter.add("a", i64::MAX);
+    let json = serde_json::to_string(&counter).unwrap();
+    assert_eq!(json, r#"{"p":{"a":"27670116110564327421"},"n":{}}"#);
+    let back: PnCounter = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, counter);
+
+    // Tallies written as plain u64 numbers still read.
+    let old: PnCounter = serde_json::from_str(r#"{"p":{"a":18446744073709551615},"n":{}}"#).unwrap();
+    assert_eq!(old.total().to_string(), "18446744073709551615");
+}
@@ -0,0 +1,77 @@
+#[macro_use]
+extern crate quickcheck;
+extern crate shared;
+
+use shared::{Command, Config, PnCounter, State};
+
+/// Builds a counter from `(replica, delta)` operations over a handful of
+/// replicas, so generated counters overlap.
+fn build(ops: &[(u8, i16)]) -> PnCounter {
+    let mut counter = PnCounter::new();
+    for &(replica, delta) in ops {
+        counter.add(&format!("r{}", replica % 4), i64::from(delta));
+    }
+    counter
+}
+
+fn merged(a: &PnCounter, b: &PnCounter) -> PnCounter {
+    let mut out = a.clone();
+    out.merge(b);
+    out
+}
+
+quickcheck! {
+    fn merge_is_commutative(a: Vec<(u8, i16)>, b: Vec<(u8, i16)>) -> bool {
+        let (a, b) = (build(&a), build(&b));
+        merged(&a, &b) == merged(&b, &a)
+    }
+
+    fn merge_is_associative(a: Vec<(u8, i16)>, b: Vec<(u8, i16)>, c: Vec<(u8, i16)>) -> bool {
+        let (a, b, c) = (build(&a), build(&b), build(&c));
+        merged(&merged(&a, &b), &c) == merged(&a, &merged(&b, &c))
+    }
+
+    fn merge_is_idempotent(a: Vec<(u8, i16)>, b: Vec<(u8, i16)>) -> bool {
+        let ab = merged(&build(&a), &build(&b));
+        merged(&ab, &ab) == ab && merged(&ab, &build(&b)) == ab
+    }
+
+    fn delta_restores_state(base: Vec<(u8, i16)>, more: Vec<(u8, i16)>) -> bool {
+        let before = build(&base);
+        let mut after = before.clone();
+        for &(replica, delta) in &more {
+            after.add(&format!("r{}", replica % 4), i64::from(delta));
+        }
+        merged(&before, &after.delta(&before)) == after
+    }
+
+    fn concurrent_updates_are_kept(a: Vec<i16>, b: Vec<i16>) -> bool {
+        let mut left = PnCounter::new();
+        let mut right = PnCounter::new();
+        for &delta in &a {
+            left.add("left", i64::from(delta));
+        }
+        for &delta in &b {
+            right.add("right", i64::from(delta));
+        }
+        let expected: i64 = a.iter().chain(&b).map(|&d| i64::from(d)).sum();
+        merged(&left, &right).value() == expected
+    }
+}
+
+#[test]
+fn delta_is_empty_without_changes() {
+    let counter = build(&[(0, 3), (1, -2)]);
+    assert!(counter.delta(&counter).is_empty());
+}
+
+#[test]
+fn starting_values_count_once_across_copies() {
+    let config = Config::default();
+    let mut left = State::initial(10);
+    let mut right = State::initial(10);
+    left.apply("left", &config, &Command::Increment).unwrap();
+    right.apply("right", &config, &Command::Increment).unwrap();
+    left.counter.merge(&right.counter);
+    assert_eq!(left.value(), 12);
+}